@@ -1,28 +1,45 @@
 pub mod angles;
+pub mod broadphase;
 pub mod collision;
+pub mod contact;
+pub mod determinism;
+pub mod events;
+pub mod forces;
+pub mod integrator;
+pub mod joints;
+pub mod kinematic;
+pub mod particles;
 pub mod primitives;
+pub mod raycast;
+pub mod rigid_body;
+pub mod sleep;
 pub mod vector;
+pub mod world;
 
 use core::{f32, fmt};
 use std::cmp::PartialEq;
 use std::cmp::PartialOrd;
 use std::fmt::Debug;
-use std::sync::LazyLock;
 
+pub use forces::ForceGenerator;
+pub use integrator::Integrator;
+pub use joints::{Anchor, Joint, JointKind};
+pub use kinematic::{Easing, Path, PathMode};
+pub use particles::{Emitter, ParticleSystem};
+pub use rigid_body::RigidBody;
 use uuid::Uuid;
 use vector::EuclidianVector;
+pub use world::{Boundaries, BoundaryMode, Bounds, PhysicsWorld, WorldConfig};
 
 pub const BACKGROUND: char = ' ';
 
-const TIME_STEP: f32 = 0.01; // defines the interval of the physics calculation
-pub const DEFAULT_WINDOW: (u16, u16) = (50, 10); // defines the viewing area and physical boundary
-const MAX_VEL: f32 = 20.0;
-const MAX_ACC: f32 = 1_000.0;
+pub const DEFAULT_WINDOW: (u16, u16) = (50, 10); // defines the default viewing area
 const _MAX_MASS: f32 = 1_000.0;
 
-// initialise the window boundary once at runtime by checking the size of the terminal
-static WINDOW: LazyLock<(u16, u16)> =
-    LazyLock::new(|| termion::terminal_size().unwrap_or(DEFAULT_WINDOW));
+/// the collision layer every entity is on by default.
+pub const LAYER_DEFAULT: u32 = 1;
+/// a mask that collides with entities on every layer.
+pub const LAYER_ALL: u32 = u32::MAX;
 
 /// defines a vector of entities
 pub type Entities = Vec<Entity>;
@@ -41,6 +58,27 @@ pub enum EntityType {
     Npc,
     Player,
     Static,
+    /// moves along its path (see `Entity::path`), ignoring every force,
+    /// and pushes (or carries) whatever it runs into, like a moving platform.
+    Kinematic,
+}
+
+/// The shape used to detect collisions with other entities,
+/// sized by the entity's `hit_radius` unless stated otherwise.
+#[derive(Default, PartialEq, Clone, Debug)]
+pub enum Hitbox {
+    /// axis-aligned square, the `hit_radius` is its apothem.
+    /// it never rotates (see `Entity::moment_of_inertia`).
+    #[default]
+    Square,
+    /// axis-aligned rectangle, with its own half-width and half-height
+    /// (ignoring the `hit_radius`), which never rotates either.
+    Aabb { half_width: f32, half_height: f32 },
+    /// true circle, collisions are resolved along the line between centroids.
+    Circle,
+    /// convex polygon, with vertices given relative to the entity's position (its centroid),
+    /// and rotated along with the entity's orientation.
+    Polygon(Vec<(f32, f32)>),
 }
 
 #[derive(Clone, Debug)]
@@ -57,8 +95,56 @@ pub struct Entity {
     pub mass: f32,
     pub hit_radius: f32,
 
+    /// rotation about the centroid in radians (positive is clockwise on screen),
+    /// and the rate at which that rotation is changing.
+    pub orientation: f32,
+    pub angular_vel: f32,
+
+    pub hitbox: Hitbox,
+
+    /// coefficient of restitution: 0.0 comes to a dead stop, 1.0 is perfectly bouncy.
+    pub restitution: f32,
+
+    /// coefficients of friction whilst at rest and whilst sliding against other surfaces.
+    pub static_friction: f32,
+    pub kinetic_friction: f32,
+
     // forces applied to and exerted by entity
     pub input_force: EuclidianVector,
+    pub torque: f32,
+
+    /// the compound body this entity is a part of, if any (see `RigidBody`)
+    pub body: Option<Uuid>,
+
+    /// fast movers are swept along their path each step, so they can't skip through
+    /// thin static walls (see `collision::sweep`).
+    pub bullet: bool,
+
+    /// asleep entities are left out of the simulation until something disturbs them,
+    /// they fall asleep after being (almost) still for some number of ticks (see `sleep`).
+    pub asleep: bool,
+    pub idle_ticks: u32,
+
+    /// bitfields of the collision layers this entity is on, and the layers it collides with,
+    /// two entities only collide if each is on a layer the other collides with.
+    /// e.g. debris on its own layer, with a mask of just the terrain's layer.
+    pub category: u32,
+    pub mask: u32,
+
+    /// how many more seconds the entity exists for, before it is removed from the world.
+    pub ttl: Option<f32>,
+
+    /// sensors report when other entities overlap them (see `events`),
+    /// but don't push or get pushed by anything they touch.
+    pub sensor: bool,
+
+    /// the route followed by a kinematic entity, ignored by every other type of entity.
+    pub path: Option<Path>,
+
+    /// one-way entities only stop what reaches them through one of their sides, given as the
+    /// direction that side faces, anything else passes straight through them.
+    /// e.g. `(0.0, -1.0)` for a platform that can be jumped up through and landed on.
+    pub one_way: Option<EuclidianVector>,
 
     // misc fields (subject to imminent change)
     pub grounded: bool,
@@ -70,15 +156,45 @@ impl Entity {
             EntityType::Player => 1.0,
             EntityType::Npc => 1.0,
             EntityType::Static => 1.0,
+            EntityType::Kinematic => 1.0,
         };
+        let restitution: f32 = match id {
+            EntityType::Player => 0.0,
+            EntityType::Npc => 0.5,
+            EntityType::Static => 0.0,
+            EntityType::Kinematic => 0.0,
+        };
+        let (static_friction, kinetic_friction) = default_friction(id);
 
         Entity {
             id,
             pos,
             mass,
+            restitution,
+            static_friction,
+            kinetic_friction,
             ..Default::default()
         }
     }
+
+    /// creates a kinematic entity at the start of the path, ready to follow it.
+    pub fn kinematic(path: Path) -> Entity {
+        let pos = path.position().unwrap_or_default();
+        Entity {
+            path: Some(path),
+            ..Entity::new(EntityType::Kinematic, pos)
+        }
+    }
+}
+
+/// returns the coefficients of static and kinetic friction each type of entity starts with.
+fn default_friction(id: EntityType) -> (f32, f32) {
+    match id {
+        EntityType::Player => (1.0, 0.8),
+        EntityType::Npc => (0.6, 0.4),
+        EntityType::Static => (0.6, 0.4),
+        EntityType::Kinematic => (1.0, 0.8),
+    }
 }
 
 impl Default for Entity {
@@ -92,31 +208,119 @@ impl Default for Entity {
             acc: EuclidianVector::new(0.0, 0.0),
             mass: 1.0,
             input_force: EuclidianVector::new(0.0, 0.0),
+            torque: 0.0,
             hit_radius: 0.5,
+            orientation: 0.0,
+            angular_vel: 0.0,
+            hitbox: Hitbox::Square,
+            restitution: 0.5,
+            static_friction: 0.6,
+            kinetic_friction: 0.4,
+            body: None,
+            bullet: false,
+            asleep: false,
+            idle_ticks: 0,
+            category: LAYER_DEFAULT,
+            mask: LAYER_ALL,
+            sensor: false,
+            ttl: None,
+            path: None,
+            one_way: None,
             grounded: false,
         }
     }
 }
 
-/// performs force and motion calculations on all the passed entities
-pub fn update(entities: &mut [Entity]) {
-    // WARNING: comparing each entity against ALL other entities on the scene
-    // yields the WORST-CASE compute performance (n^2) -- serves as the baseline.
-    let comparison_entities = entities.to_owned();
-
-    // update motion parameters based on the applied forces
-    for entity in entities.iter_mut() {
-        // statics don't move
-        if entity.id == EntityType::Static {
-            continue;
+impl Entity {
+    /// returns true if nothing can push the entity,
+    /// statics never move, and kinematics only move along their path.
+    pub fn immovable(&self) -> bool {
+        matches!(self.id, EntityType::Static | EntityType::Kinematic)
+    }
+
+    /// returns the reciprocal of the entity's mass,
+    /// immovable entities behave as if they have infinite mass.
+    pub fn inverse_mass(&self) -> f32 {
+        if self.immovable() || self.mass <= 0.0 {
+            0.0
+        } else {
+            1.0 / self.mass
+        }
+    }
+
+    /// returns true if both entities are alive, and their collision layers allow them to collide.
+    /// dead and hidden entities pass through everything.
+    pub fn collides_with(&self, other: &Entity) -> bool {
+        let alive = |entity: &Entity| matches!(entity.state, EntityState::Alive);
+        alive(self)
+            && alive(other)
+            && self.category & other.mask != 0
+            && other.category & self.mask != 0
+    }
+
+    /// returns the entity's resistance to being rotated about its centroid.
+    /// axis-aligned boxes (the default hitbox) can't rotate, so they have infinite moment of
+    /// inertia, and are never spun by torques or off-center contacts. Debris that should tumble
+    /// needs a circle or polygon hitbox instead (e.g. a square polygon rather than a `Square`).
+    pub fn moment_of_inertia(&self) -> f32 {
+        match &self.hitbox {
+            Hitbox::Square | Hitbox::Aabb { .. } => f32::INFINITY,
+            Hitbox::Circle => 0.5 * self.mass * self.hit_radius * self.hit_radius,
+            Hitbox::Polygon(vertices) => {
+                // sum the contributions of the triangles fanning out from the centroid
+                // (See http://enwp.org/List_of_moments_of_inertia)
+                let (mut numerator, mut denominator) = (0.0, 0.0);
+                for (i, a) in vertices.iter().enumerate() {
+                    let b = &vertices[(i + 1) % vertices.len()];
+                    let (a, b) = (
+                        EuclidianVector::new(a.0, a.1),
+                        EuclidianVector::new(b.0, b.1),
+                    );
+                    let area = a.cross(&b).abs();
+                    numerator += area * (a.dot(&a) + a.dot(&b) + b.dot(&b));
+                    denominator += area;
+                }
+                // polygons without any area (e.g. a line) can't be spun about their centroid
+                if denominator <= 0.0 {
+                    return f32::INFINITY;
+                }
+                self.mass * numerator / (6.0 * denominator)
+            }
+        }
+    }
+
+    /// returns the half-width and half-height of the smallest axis-aligned box
+    /// (centered on the entity) that contains the entity's hitbox.
+    pub fn half_extents(&self) -> (f32, f32) {
+        match &self.hitbox {
+            Hitbox::Square | Hitbox::Circle => (self.hit_radius, self.hit_radius),
+            Hitbox::Aabb {
+                half_width,
+                half_height,
+            } => (*half_width, *half_height),
+            Hitbox::Polygon(vertices) => {
+                let polygon = primitives::Polygon::new(&(0.0, 0.0), vertices, self.orientation);
+                polygon
+                    .vertices()
+                    .iter()
+                    .fold((0.0, 0.0), |(w, h), (x, y)| {
+                        (x.abs().max(w), y.abs().max(h))
+                    })
+            }
+        }
+    }
+
+    /// returns the reciprocal of the entity's moment of inertia,
+    /// immovable entities behave as if they have infinite moment of inertia.
+    pub fn inverse_inertia(&self) -> f32 {
+        let inertia = self.moment_of_inertia();
+        if self.immovable() || inertia <= 0.0 || !inertia.is_finite() {
+            0.0
+        } else {
+            1.0 / inertia
         }
-        // handle forces generated due to contact with other entities
-        collision::pairwise(entity, &comparison_entities);
-        entity.update();
     }
-}
 
-impl Entity {
     /// apply a force vector to the associated entity to affect its
     /// acceleration vector on the next update (F = m * a)
     pub fn apply_force(&mut self, force: EuclidianVector) {
@@ -124,53 +328,106 @@ impl Entity {
         self.input_force.y += force.y;
     }
 
+    /// apply a torque to the associated entity to affect its
+    /// angular acceleration on the next update (T = I * alpha).
+    /// returns false, leaving the torque unapplied, if the entity can't be turned at all,
+    /// i.e. axis-aligned boxes (the default hitbox, see `moment_of_inertia`) and immovable entities.
+    pub fn apply_torque(&mut self, torque: f32) -> bool {
+        if self.inverse_inertia() <= 0.0 {
+            return false;
+        }
+        self.torque += torque;
+        true
+    }
+
+    /// apply a force vector at some point in space,
+    /// which also generates a torque if that point is off-center (T = r x F),
+    /// though only entities that can rotate are spun by it (see `moment_of_inertia`).
+    pub fn apply_force_at_point(&mut self, force: EuclidianVector, point: (f32, f32)) {
+        let arm = EuclidianVector::from(self.pos, point);
+        self.apply_torque(arm.cross(&force));
+        self.apply_force(force);
+    }
+
+    /// returns the direction the entity is facing, in degrees counter-clockwise from the +x axis,
+    /// snapped to the nearest of some number of segments (see `angles::map_angle`).
+    pub fn heading(&self, segment_count: usize) -> f32 {
+        // orientation is clockwise on screen, whereas angles are measured counter-clockwise
+        let degrees = (-self.orientation.to_degrees()).rem_euclid(360.0);
+        angles::map_angle(degrees, segment_count)
+    }
+
     /// returns the force required to drive the entity to the target acceleration
     pub fn target_acc(&mut self, ax: f32, ay: f32) -> EuclidianVector {
         EuclidianVector::new(self.mass * ax, self.mass * ay)
     }
 
     /// returns the force required to drive the entity to the target velocity
-    pub fn target_vel(&mut self, vx: f32, vy: f32) -> EuclidianVector {
+    pub fn target_vel(&mut self, vx: f32, vy: f32, config: &WorldConfig) -> EuclidianVector {
+        let dt = config.time_step;
         EuclidianVector::new(
-            self.mass * (vx - self.vel.x) / TIME_STEP,
-            self.mass * (vy - self.vel.y) / TIME_STEP,
+            self.mass * (vx - self.vel.x) / dt,
+            self.mass * (vy - self.vel.y) / dt,
         )
     }
 
     /// returns the force required to drive the entity to the target position
+    /// (within a single time step, using the world's integrator)
     /// https://www.ncl.ac.uk/webtemplate/ask-assets/external/maths-resources/mechanics/kinematics/equations-of-motion.html
-    pub fn target_pos(&mut self, x: f32, y: f32) -> EuclidianVector {
+    pub fn target_pos(&mut self, x: f32, y: f32, config: &WorldConfig) -> EuclidianVector {
+        let dt = config.time_step;
         let (x0, y0) = self.pos;
         let (vx, vy) = (self.vel.x, self.vel.y);
         let m = self.mass;
+        let k = config.integrator.displacement_factor();
         EuclidianVector::new(
-            (2.0 / 3.0) * (x - x0 - (vx * TIME_STEP)) * m / (TIME_STEP * TIME_STEP),
-            (2.0 / 3.0) * (y - y0 - (vy * TIME_STEP)) * m / (TIME_STEP * TIME_STEP),
+            (1.0 / k) * (x - x0 - (vx * dt)) * m / (dt * dt),
+            (1.0 / k) * (y - y0 - (vy * dt)) * m / (dt * dt),
         )
     }
 
-    /// update entity position using motion equations and Newton's 2nd Law:
-    /// x1 = x0 + vt + 0.5at^2
-    /// v1 = v0 + at
+    /// update entity position using motion equations and Newton's 2nd Law,
+    /// with the world's integrator (see `Integrator`):
     /// F = m * a
-    pub fn update(&mut self) {
+    pub fn update(&mut self, config: &WorldConfig) {
+        self.update_with(config, |_, _| EuclidianVector::new(0.0, 0.0));
+    }
+
+    /// same as `update`, but also pushed by forces that change with the entity's position and
+    /// velocity (e.g. springs or drag), which are worked out again at every stage of the integrator.
+    pub fn update_with<F>(&mut self, config: &WorldConfig, forces: F)
+    where
+        F: Fn((f32, f32), &EuclidianVector) -> EuclidianVector,
+    {
+        let dt = config.time_step;
+        let integrator = config.integrator;
+
         // determine the resultant acceleration from the applied forces
         // constant force means constant acceleration
-        self.acc.x = self.input_force.x / self.mass;
-        self.acc.y = self.input_force.y / self.mass;
+        let (input_force, mass) = (self.input_force.clone(), self.mass);
+        let acceleration =
+            |force: EuclidianVector| EuclidianVector::new(force.x / mass, force.y / mass);
+        self.acc = acceleration(input_force.clone() + forces(self.pos, &self.vel));
 
         // determine entity motion
         // constant velocity means no force is being applied
-        self.vel.x += self.acc.x * TIME_STEP;
-        self.vel.y += self.acc.y * TIME_STEP;
-        self.pos.0 += self.vel.x * TIME_STEP + 0.5 * self.acc.x * TIME_STEP * TIME_STEP;
-        self.pos.1 += self.vel.y * TIME_STEP + 0.5 * self.acc.y * TIME_STEP * TIME_STEP;
+        (self.pos, self.vel) = integrator.integrate(self.pos, &self.vel, dt, |pos, vel| {
+            acceleration(input_force.clone() + forces(pos, vel))
+        });
+
+        // same again for the rotational motion
+        let angular_acc = self.torque * self.inverse_inertia();
+        let (orientation, angular_vel) =
+            integrator.integrate_angle(self.orientation, self.angular_vel, dt, |_, _| angular_acc);
+        self.orientation = orientation.rem_euclid(f32::consts::TAU);
+        self.angular_vel = angular_vel;
 
         // "consume" the applied forces
         self.input_force = EuclidianVector::new(0.0, 0.0);
+        self.torque = 0.0;
 
         // apply constraints
-        self.constrain();
+        self.constrain(config);
     }
 }
 
@@ -190,44 +447,143 @@ impl fmt::Display for Entity {
                 EntityState::Hidden => write!(f, "{BACKGROUND}"),
             },
             EntityType::Static => write!(f, "\x1b[47m \x1b[0m"),
+            EntityType::Kinematic => write!(f, "\x1b[46m \x1b[0m"),
         }
     }
 }
 
-#[derive(Clone, Debug)]
-/// Represents a collection of entities that are treated as a single larger entity
-pub struct RigidBody {
-    pub parts: Entities,
-}
-
-impl fmt::Display for RigidBody {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for entity in self.parts.iter() {
-            write!(f, "{entity}")?
-        }
-        Ok(())
-    }
-}
-
 impl Entity {
-    fn constrain(&mut self) {
+    fn constrain(&mut self, config: &WorldConfig) {
+        let (max_vel, max_acc) = (config.max_vel, config.max_acc);
         //
         // limit velocity
-        constraint(&mut self.vel.x, -MAX_VEL, MAX_VEL);
-        constraint(&mut self.vel.y, -MAX_VEL, MAX_VEL);
+        constraint(&mut self.vel.x, -max_vel, max_vel);
+        constraint(&mut self.vel.y, -max_vel, max_vel);
         //
         // limit acceleration
-        constraint(&mut self.acc.x, -MAX_ACC, MAX_ACC);
-        constraint(&mut self.acc.y, -MAX_ACC, MAX_ACC);
+        constraint(&mut self.acc.x, -max_acc, max_acc);
+        constraint(&mut self.acc.y, -max_acc, max_acc);
         //
-        // limit position to window
-        if constraint(&mut self.pos.0, 1.0_f32, (WINDOW.0 - 1) as f32) {
+        self.contain(config);
+    }
+
+    /// keeps the entity within the world's bounds, as each of its edges dictates.
+    pub(crate) fn contain(&mut self, config: &WorldConfig) {
+        let (bounds, edges) = (&config.bounds.inset_for(self), &config.boundaries);
+        if self.pos.0 <= bounds.left {
+            past_edge(
+                edges.left,
+                &mut self.pos.0,
+                &mut self.vel.x,
+                bounds.left,
+                bounds.right,
+            );
+        } else if self.pos.0 >= bounds.right {
+            past_edge(
+                edges.right,
+                &mut self.pos.0,
+                &mut self.vel.x,
+                bounds.right,
+                bounds.left,
+            );
+        }
+        if self.pos.1 <= bounds.top {
+            past_edge(
+                edges.top,
+                &mut self.pos.1,
+                &mut self.vel.y,
+                bounds.top,
+                bounds.bottom,
+            );
+        } else if self.pos.1 >= bounds.bottom {
+            let (pos, vel) = (&mut self.pos.1, &mut self.vel.y);
+            if let Some(speed) = past_edge(edges.bottom, pos, vel, bounds.bottom, bounds.top) {
+                // the floor pushes back against whatever was driving the entity into it
+                if speed > 0.0 {
+                    self.floor_friction(speed);
+                }
+                self.grounded = true;
+            }
+        }
+    }
+
+    /// returns the direction (along each axis) out through any edge of the world that the entity
+    /// is resting against and that holds entities in (rather than letting them through), or zero.
+    pub(crate) fn edges_touched(&self, config: &WorldConfig) -> (f32, f32) {
+        let (bounds, edges) = (&config.bounds.inset_for(self), &config.boundaries);
+        let holds = |mode: BoundaryMode| matches!(mode, BoundaryMode::Clamp | BoundaryMode::Bounce);
+        let touched = |pos: f32, low: f32, high: f32, low_mode, high_mode| {
+            if pos <= low && holds(low_mode) {
+                -1.0
+            } else if pos >= high && holds(high_mode) {
+                1.0
+            } else {
+                0.0
+            }
+        };
+        (
+            touched(
+                self.pos.0,
+                bounds.left,
+                bounds.right,
+                edges.left,
+                edges.right,
+            ),
+            touched(
+                self.pos.1,
+                bounds.top,
+                bounds.bottom,
+                edges.top,
+                edges.bottom,
+            ),
+        )
+    }
+
+    /// opposes the entity sliding along the floor of the world,
+    /// given the speed at which the entity was being driven into the floor.
+    /// the floor is as rough as a static, combining friction with the entity's the same way.
+    fn floor_friction(&mut self, normal_speed: f32) {
+        let (floor_static, floor_kinetic) = default_friction(EntityType::Static);
+        let static_friction = (self.static_friction * floor_static).sqrt();
+        let kinetic_friction = (self.kinetic_friction * floor_kinetic).sqrt();
+        if self.vel.x.abs() <= static_friction * normal_speed {
             self.vel.x = 0.0;
+        } else {
+            self.vel.x -= self.vel.x.signum() * kinetic_friction * normal_speed;
+        }
+    }
+}
+
+/// Moves an entity (along one axis) that has reached an edge of the world, as the edge's mode
+/// dictates, given the position of the edge and of the opposite edge.
+/// returns the speed the entity was moving into the edge, if it has been stopped at the edge.
+fn past_edge(
+    mode: BoundaryMode,
+    pos: &mut f32,
+    vel: &mut f32,
+    edge: f32,
+    opposite: f32,
+) -> Option<f32> {
+    // how fast the entity is moving out through the edge
+    let outwards = (edge - opposite).signum();
+    let speed = (*vel * outwards).max(0.0);
+
+    match mode {
+        BoundaryMode::Bounce if speed >= collision::RESTITUTION_TRIGGER_VEL => {
+            *pos = 2.0 * edge - *pos;
+            *vel = -*vel;
+            None
+        }
+        BoundaryMode::Clamp | BoundaryMode::Bounce => {
+            *pos = edge;
+            *vel = 0.0;
+            Some(speed)
         }
-        if constraint(&mut self.pos.1, 1.0_f32, (WINDOW.1 - 1) as f32) {
-            self.vel.y = 0.0;
-            self.grounded = true;
+        BoundaryMode::Wrap if *pos != edge => {
+            *pos += opposite - edge;
+            None
         }
+        BoundaryMode::Wrap | BoundaryMode::Despawn => None,
     }
 }
 
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use crate::entity::Entity;
+
+// -------------------------------------------------------------------------- //
+// ------------------- BROADPHASE (UNIFORM GRID / SPATIAL HASH) ------------- //
+// -------------------------------------------------------------------------- //
+
+/// A grid-cell coordinate.
+/// Cells are one unit wide and centered on whole numbers, so they line up with
+/// the terminal coordinates used to key a `scene_map::EntityMap`
+/// (see `scene::term_coords`), but are signed so entities beyond the
+/// top/left of the screen can still be hashed.
+pub type Cell = (i32, i32);
+
+/// returns the grid-cell containing the given point.
+///
+/// # Examples
+///
+/// ```rust
+/// use ascii_arcade::entity::broadphase::cell;
+/// assert_eq!(cell((1.0, 1.0)), (1, 1));
+/// assert_eq!(cell((1.4, 0.6)), (1, 1));
+/// assert_eq!(cell((-0.6, 2.5)), (-1, 3));
+/// ```
+pub fn cell(point: (f32, f32)) -> Cell {
+    (point.0.round() as i32, point.1.round() as i32)
+}
+
+/// Maps grid-cells to the (indices of the) entities whose hitboxes overlap them.
+/// Entities can only be touching if they share at least one cell,
+/// so narrowphase tests only need to run against the entities in those cells.
+pub struct SpatialHash {
+    cells: HashMap<Cell, Vec<usize>>,
+}
+
+impl SpatialHash {
+    /// builds the spatial hash from a slice of entities,
+    /// entities are referred to by their index into that slice.
+    pub fn new(entities: &[Entity]) -> SpatialHash {
+        SpatialHash::new_filtered(entities, |_| true)
+    }
+
+    /// builds the spatial hash from only the entities passing the filter,
+    /// which are still referred to by their index into the whole slice.
+    pub fn new_filtered<F>(entities: &[Entity], filter: F) -> SpatialHash
+    where
+        F: Fn(&Entity) -> bool,
+    {
+        let mut cells: HashMap<Cell, Vec<usize>> = HashMap::new();
+        for (idx, entity) in entities.iter().enumerate() {
+            if !filter(entity) {
+                continue;
+            }
+            for key in cells_spanned(entity) {
+                cells.entry(key).or_default().push(idx);
+            }
+        }
+        SpatialHash { cells }
+    }
+
+    /// returns the indices of all entities that share a cell with the passed entity,
+    /// in ascending order and without duplicates (may include the entity itself).
+    pub fn candidates(&self, entity: &Entity) -> Vec<usize> {
+        let (x, y) = entity.pos;
+        let (w, h) = entity.half_extents();
+        self.within((x - w, y - h), (x + w, y + h))
+    }
+
+    /// returns the indices of all entities that share a cell with the box between
+    /// the (top-left, bottom-right) corners, in ascending order and without duplicates.
+    pub fn within(&self, min: (f32, f32), max: (f32, f32)) -> Vec<usize> {
+        let mut nearby: Vec<usize> = cells_between(min, max)
+            .filter_map(|key| self.cells.get(&key))
+            .flatten()
+            .copied()
+            .collect();
+
+        // keep the same ordering a brute-force scan of the slice would use,
+        // so the results of the narrowphase are unaffected by the broadphase.
+        nearby.sort_unstable();
+        nearby.dedup();
+        nearby
+    }
+}
+
+/// returns every grid-cell overlapped by the entity's hitbox.
+fn cells_spanned(entity: &Entity) -> impl Iterator<Item = Cell> {
+    let (x, y) = entity.pos;
+    let (w, h) = entity.half_extents();
+    cells_between((x - w, y - h), (x + w, y + h))
+}
+
+/// returns every grid-cell overlapped by the box between the (top-left, bottom-right) corners.
+fn cells_between(min: (f32, f32), max: (f32, f32)) -> impl Iterator<Item = Cell> {
+    let (x_min, y_min) = cell(min);
+    let (x_max, y_max) = cell(max);
+    (x_min..=x_max).flat_map(move |i| (y_min..=y_max).map(move |j| (i, j)))
+}
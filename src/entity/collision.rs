@@ -1,142 +1,829 @@
 use core::f32;
+use std::collections::{HashMap, HashSet};
 
+use uuid::Uuid;
+
+use super::broadphase::SpatialHash;
+use super::contact::{Contact, Manifold};
+use super::events::CONTACT_MARGIN;
+use super::rigid_body::Bodies;
+use super::sleep::at_rest;
 use super::vector::EuclidianVector;
-use crate::entity::primitives::Square;
-use crate::entity::Entity;
+use crate::entity::primitives::{Aabb, Circle, Polygon, Square};
+use crate::entity::{Entity, EntityType, Hitbox, WorldConfig};
 
-const COLLISION_TRIGGER_VEL: f32 = 10.0;
+/// collisions closing in slower than this are treated as perfectly inelastic.
+pub(crate) const RESTITUTION_TRIGGER_VEL: f32 = 1.0;
 
-/// Applies forces generated due to contact with other entities.
-pub fn pairwise(entity: &mut Entity, other_entities: &Vec<Entity>) {
-    entity.grounded = false;
+/// number of passes made over all the contacts when applying impulses.
+const SOLVER_ITERATIONS: usize = 4;
 
-    let _entity = entity.clone();
-    let entity_hitbox = Square::new(&_entity.pos, &_entity.hit_radius);
+/// fraction of the overlap between entities that gets corrected on each pass over the contacts.
+const CORRECTION_RATIO: f32 = 0.2;
 
-    // gathers a list of references to entities colliding with the entity under test
-    let mut colliders: Vec<&Entity> = Vec::new();
+/// overlap between entities that is tolerated without correction.
+const CORRECTION_SLOP: f32 = 0.01;
 
-    for other_entity in other_entities {
-        if entity.uuid == other_entity.uuid {
-            continue;
+/// how far into a one-way entity another entity can start a step, and still be stopped by it,
+/// entities resting on a one-way platform are left barely overlapping it.
+const ONE_WAY_MARGIN: f32 = 0.1;
+
+/// how far past a face of an immovable entity to look for another immovable entity covering it.
+const SEAM_PROBE: f32 = 0.01;
+
+/// The primitive used to test for intersections with an entity's hitbox.
+pub enum Shape<'a> {
+    Square(Square<'a>),
+    Aabb(Aabb<'a>),
+    Circle(Circle<'a>),
+    Polygon(Polygon),
+}
+
+impl Shape<'_> {
+    /// returns the primitive matching the entity's hitbox.
+    pub fn of(entity: &Entity) -> Shape<'_> {
+        match &entity.hitbox {
+            Hitbox::Square => Shape::Square(Square::new(&entity.pos, &entity.hit_radius)),
+            Hitbox::Aabb {
+                half_width,
+                half_height,
+            } => Shape::Aabb(Aabb::new(&entity.pos, *half_width, *half_height)),
+            Hitbox::Circle => Shape::Circle(Circle::new(&entity.pos, &entity.hit_radius)),
+            Hitbox::Polygon(vertices) => {
+                Shape::Polygon(Polygon::new(&entity.pos, vertices, entity.orientation))
+            }
         }
-        // define hitboxes to determine if entitie are colliding
-        let other_hitbox = Square::new(&other_entity.pos, &other_entity.hit_radius);
+    }
 
-        if entity_hitbox.overlap(&other_hitbox).is_some() {
-            colliders.push(other_entity);
+    /// returns the manifold describing how this shape is touching the other shape.
+    pub fn contact(&self, other: &Shape) -> Option<Manifold> {
+        match (self, other) {
+            (Shape::Square(a), Shape::Square(b)) => a.contact(b),
+            (Shape::Circle(a), Shape::Circle(b)) => a.contact(b),
+            (Shape::Circle(a), Shape::Square(b)) => a.contact_square(b),
+            (Shape::Square(a), Shape::Circle(b)) => b.contact_square(a).map(Manifold::flip),
+            (Shape::Polygon(a), Shape::Polygon(b)) => a.contact(b),
+            (Shape::Polygon(a), Shape::Circle(b)) => a.contact_circle(b),
+            (Shape::Circle(a), Shape::Polygon(b)) => b.contact_circle(a).map(Manifold::flip),
+            (Shape::Polygon(a), Shape::Square(b)) => a.contact(&Polygon::from_square(b)),
+            (Shape::Square(a), Shape::Polygon(b)) => Polygon::from_square(a).contact(b),
+            (Shape::Aabb(a), Shape::Aabb(b)) => a.contact(b),
+            (Shape::Aabb(a), Shape::Square(b)) => a.contact(&Aabb::from_square(b)),
+            (Shape::Square(a), Shape::Aabb(b)) => Aabb::from_square(a).contact(b),
+            (Shape::Circle(a), Shape::Aabb(b)) => a.contact_aabb(b),
+            (Shape::Aabb(a), Shape::Circle(b)) => b.contact_aabb(a).map(Manifold::flip),
+            (Shape::Polygon(a), Shape::Aabb(b)) => a.contact(&Polygon::from_aabb(b)),
+            (Shape::Aabb(a), Shape::Polygon(b)) => Polygon::from_aabb(a).contact(b),
         }
     }
 
-    // no colliders detected, exit
-    if colliders.is_empty() {
-        return;
+    /// returns true if the point lies inside (or on the boundary of) this shape.
+    pub fn contains(&self, point: &(f32, f32)) -> bool {
+        match self {
+            Shape::Square(square) => Aabb::from_square(square).contains(point),
+            Shape::Aabb(aabb) => aabb.contains(point),
+            Shape::Circle(circle) => circle.contains(point),
+            Shape::Polygon(polygon) => polygon.contains(point),
+        }
     }
 
-    // used to average values
-    let ratio: f32 = 1.0 / colliders.len() as f32;
+    /// returns the distance along a ray (with unit-length direction) until it first meets
+    /// this shape, and the shape's surface normal at that point.
+    pub fn raycast(
+        &self,
+        origin: &(f32, f32),
+        direction: &EuclidianVector,
+    ) -> Option<(f32, EuclidianVector)> {
+        match self {
+            Shape::Square(square) => Aabb::from_square(square).raycast(origin, direction),
+            Shape::Aabb(aabb) => aabb.raycast(origin, direction),
+            Shape::Circle(circle) => circle.raycast(origin, direction),
+            Shape::Polygon(polygon) => polygon.raycast(origin, direction),
+        }
+    }
+}
 
-    // create a single equivalent collider from all the colliding entities
-    let mass_avg: f32 = colliders.iter().map(|e| e.mass).sum();
-    let mut pos_avg: (f32, f32) = (0.0, 0.0);
-    let mut vel_avg = EuclidianVector::new(0.0, 0.0);
-    let mut acc_avg = EuclidianVector::new(0.0, 0.0);
+/// Returns the contact between two entities if their hitboxes are overlapping.
+/// The contact normal is directed from the first entity towards the second.
+pub fn narrowphase(entity: &Entity, other: &Entity) -> Option<Contact> {
+    let manifold = Shape::of(entity).contact(&Shape::of(other))?;
 
-    for collider in &colliders[..] {
-        pos_avg = (pos_avg.0 + collider.pos.0, pos_avg.1 + collider.pos.1);
-        vel_avg += collider.vel.clone();
-        acc_avg += collider.acc.clone();
+    Some(Contact {
+        a: entity.uuid,
+        b: other.uuid,
+        manifold,
+    })
+}
+
+/// Returns every contact between the passed entities, each pair of entities is only reported once.
+/// Entities on collision layers that the other doesn't collide with are never reported,
+/// and neither are pairs of immovable entities (e.g. statics placed over one another).
+/// Asleep entities are only reported touching entities that are awake.
+pub fn detect(entities: &[Entity]) -> Vec<Contact> {
+    let grid = SpatialHash::new(entities);
+
+    // asleep entities stay put, so are only checked from the awake entities around them.
+    // pairs are checked in order, so the contacts are the same as a brute-force scan would give.
+    let mut pairs: Vec<(usize, usize)> = Vec::new();
+    for (idx, entity) in entities.iter().enumerate() {
+        if entity.asleep {
+            continue;
+        }
+        for other in grid.candidates(entity) {
+            // only check pairs one way round, and never against itself
+            if other > idx || (other < idx && entities[other].asleep) {
+                pairs.push((idx.min(other), idx.max(other)));
+            }
+        }
     }
+    pairs.sort_unstable();
+
+    let mut contacts: Vec<Contact> = Vec::new();
+    for (idx, other) in pairs {
+        let (entity, other_entity) = (&entities[idx], &entities[other]);
+        // parts of the same compound body can't collide with one another
+        if entity.body.is_some() && entity.body == other_entity.body {
+            continue;
+        }
+        if !entity.collides_with(other_entity) {
+            continue;
+        }
+        // nothing can push entities that are both immovable apart, so they never touch
+        if entity.inverse_mass() <= 0.0 && other_entity.inverse_mass() <= 0.0 {
+            continue;
+        }
+        // asleep entities stay put, so can't move into statics or one another
+        if (entity.asleep || other_entity.asleep) && at_rest(entity) && at_rest(other_entity) {
+            continue;
+        }
+        if let Some(contact) = narrowphase(entity, other_entity) {
+            contacts.push(contact);
+        }
+    }
+    contacts
+}
+
+/// Drops every contact with a one-way entity (see `Entity::one_way`) unless the other entity started
+/// the step (at `starts`) outside its open side, so entities pass through it from every other
+/// direction. The contacts that are kept push the entities apart straight out of the open side,
+/// so anything landing on a one-way platform is left resting (and grounded) on top of it.
+pub fn one_way(entities: &[Entity], starts: &[(f32, f32)], contacts: Vec<Contact>) -> Vec<Contact> {
+    if entities.iter().all(|entity| entity.one_way.is_none()) {
+        return contacts;
+    }
+    let lookup: HashMap<Uuid, usize> = entities
+        .iter()
+        .enumerate()
+        .map(|(idx, entity)| (entity.uuid, idx))
+        .collect();
+    let start = |idx: usize| starts.get(idx).copied().unwrap_or(entities[idx].pos);
+
+    contacts
+        .into_iter()
+        .filter_map(|mut contact| {
+            let (Some(&a), Some(&b)) = (lookup.get(&contact.a), lookup.get(&contact.b)) else {
+                return Some(contact);
+            };
+            // the normal runs from 'a' to 'b', so is flipped when 'b' is the one-way entity
+            for (platform, other, sign) in [(a, b, 1.0), (b, a, -1.0)] {
+                let Some(side) = entities[platform].one_way.as_ref().map(|side| side.unit()) else {
+                    continue;
+                };
+                let (p, o) = (&entities[platform], &entities[other]);
+                let started = overlap(p, o, start(platform), start(other), &side);
+                let depth = overlap(p, o, p.pos, o.pos, &side);
+                if started > ONE_WAY_MARGIN || depth <= 0.0 {
+                    return None;
+                }
+                contact.manifold.normal = side * sign;
+                contact.manifold.depth = depth;
+            }
+            Some(contact)
+        })
+        .collect()
+}
+
+/// returns how far the other entity (at some position) reaches past the side of the one-way entity
+/// (at some position) facing the given direction, negative if it is out beyond that side.
+fn overlap(
+    platform: &Entity,
+    other: &Entity,
+    platform_pos: (f32, f32),
+    other_pos: (f32, f32),
+    side: &EuclidianVector,
+) -> f32 {
+    let reach = |entity: &Entity| {
+        let (w, h) = entity.half_extents();
+        w * side.x.abs() + h * side.y.abs()
+    };
+    reach(platform) + reach(other) - EuclidianVector::from(platform_pos, other_pos).dot(side)
+}
+
+/// Drops every contact pushing a movable entity out through a face of an immovable entity that's
+/// covered by another immovable entity, like the seam between two tiles of a floor. Entities sliding
+/// across a seam would otherwise be stopped by it when they dip into the next tile, they're held up
+/// by the contact with the tile they're still on instead.
+pub fn seams(entities: &[Entity], contacts: Vec<Contact>) -> Vec<Contact> {
+    let grid = SpatialHash::new_filtered(entities, |entity| entity.inverse_mass() <= 0.0);
+    let lookup: HashMap<Uuid, usize> = entities
+        .iter()
+        .enumerate()
+        .map(|(idx, entity)| (entity.uuid, idx))
+        .collect();
+
+    contacts
+        .into_iter()
+        .filter(|contact| {
+            let (Some(&a), Some(&b)) = (lookup.get(&contact.a), lookup.get(&contact.b)) else {
+                return true;
+            };
+            !seam(entities, &grid, a, b, &contact.manifold)
+        })
+        .collect()
+}
 
-    let equivalent_single_entity = Entity {
-        mass: mass_avg * ratio,
-        pos: (pos_avg.0 * ratio, pos_avg.1 * ratio),
-        vel: vel_avg * ratio,
-        acc: acc_avg * ratio,
-        ..Entity::default()
+/// returns true if the manifold pushes the movable entity out through a covered face (see `seams`).
+fn seam(entities: &[Entity], grid: &SpatialHash, a: usize, b: usize, manifold: &Manifold) -> bool {
+    // the normal runs from 'a' to 'b', so the face of 'b' being pushed out of points back along it
+    let (fixed, moving, outward) = if entities[b].inverse_mass() <= 0.0 {
+        (b, a, manifold.normal.clone() * -1.0)
+    } else if entities[a].inverse_mass() <= 0.0 {
+        (a, b, manifold.normal.clone())
+    } else {
+        return false;
     };
+    let (fixed_entity, moving_entity) = (&entities[fixed], &entities[moving]);
 
-    let other_hitbox = &Square::new(
-        &equivalent_single_entity.pos,
-        &equivalent_single_entity.hit_radius,
+    // cast in from outside the immovable entity to find its face
+    let (w, h) = fixed_entity.half_extents();
+    let reach = w + h + 1.0;
+    let origin = (
+        fixed_entity.pos.0 + outward.x * reach,
+        fixed_entity.pos.1 + outward.y * reach,
+    );
+    let Some((distance, _)) = Shape::of(fixed_entity).raycast(&origin, &(outward.clone() * -1.0))
+    else {
+        return false;
+    };
+    let beyond = reach - distance + SEAM_PROBE;
+    let probe = (
+        fixed_entity.pos.0 + outward.x * beyond,
+        fixed_entity.pos.1 + outward.y * beyond,
     );
-    let me_to_you = EuclidianVector::from(entity.pos, equivalent_single_entity.pos).unit();
 
-    if let Some(overlap) = entity_hitbox.overlap(other_hitbox) {
-        entity.grounded = true;
+    grid.within(probe, probe).into_iter().any(|idx| {
+        let cover = &entities[idx];
+        idx != fixed
+            && idx != moving
+            && cover.inverse_mass() <= 0.0
+            && cover.one_way.is_none()
+            && cover.collides_with(moving_entity)
+            && Shape::of(cover).contains(&probe)
+    })
+}
 
-        let collide = entity
-            .collision_force(&equivalent_single_entity)
-            .unwrap_or(EuclidianVector::new(0.0, 0.0));
+/// Resolves the passed contacts by applying an impulse to each pair of touching entities,
+/// changing their velocities along the contact normal whilst conserving their momentum,
+/// then nudging them apart so they no longer overlap.
+/// Parts of compound bodies are moved together with the rest of their body.
+/// Sensors are left overlapping whatever they touch.
+pub fn resolve(entities: &mut [Entity], contacts: &[Contact]) {
+    solve(entities, contacts, None, &mut Impulses::default());
+}
 
-        // as an approximation, the direction of overlap can (in general) be treated
-        // as orthogonal to the largest overlap side.
+/// Same as `resolve`, but entities resting against the edges of the world are held up by them
+/// as if by a static (unless the edges let entities through, see `BoundaryMode`),
+/// so that piles on the floor of the window can settle.
+pub fn resolve_within(entities: &mut [Entity], contacts: &[Contact], config: &WorldConfig) {
+    solve(entities, contacts, Some(config), &mut Impulses::default());
+}
 
-        // colliding vertically
-        if overlap.0 >= overlap.1 {
-            if entity.vel.y.abs() >= COLLISION_TRIGGER_VEL
-                || equivalent_single_entity.vel.y.abs() >= COLLISION_TRIGGER_VEL
-            {
-                entity.apply_force(EuclidianVector::new(0.0, collide.y));
+/// Same as `resolve_within`, but starting from the impulses applied between the same pairs of
+/// entities last step, which are then replaced by those applied this step.
+/// Entities resting on one another need much the same impulses every step, so the impulses
+/// build up over a few steps until even tall stacks are held completely still
+/// (See "warm starting", Catto 2006).
+/// Pairs that have drifted apart since last step, but are still within the contact margin,
+/// are kept from closing the gap between them faster than they could in one step.
+pub(crate) fn resolve_warm(
+    entities: &mut [Entity],
+    contacts: &[Contact],
+    config: &WorldConfig,
+    impulses: &mut Impulses,
+) {
+    solve(entities, contacts, Some(config), impulses);
+}
+
+/// The total impulses applied between each pair of touching entities during a step,
+/// and the contact normal they were applied along.
+#[derive(Default)]
+pub(crate) struct Impulses {
+    applied: HashMap<(Uuid, Uuid), (EuclidianVector, Applied)>,
+}
+
+/// The total impulse applied between a pair of touching entities,
+/// along the contact normal and across it (by friction).
+#[derive(Clone, Copy, Default)]
+struct Applied {
+    normal: f32,
+    tangent: f32,
+}
+
+/// resolves the contacts, within the world's bounds if given any,
+/// starting from the given impulses.
+fn solve(
+    entities: &mut [Entity],
+    contacts: &[Contact],
+    config: Option<&WorldConfig>,
+    impulses: &mut Impulses,
+) {
+    let lookup: HashMap<Uuid, usize> = entities
+        .iter()
+        .enumerate()
+        .map(|(idx, entity)| (entity.uuid, idx))
+        .collect();
+    let bodies = Bodies::new(entities);
+
+    // the indices of each pair of touching entities, and how fast they bounce apart.
+    // (the bounce depends on the velocities before any impulses are applied)
+    let mut pairs: Vec<(usize, usize, Manifold, f32)> = contacts
+        .iter()
+        .filter_map(|contact| {
+            let (a, b) = (*lookup.get(&contact.a)?, *lookup.get(&contact.b)?);
+            if entities[a].sensor || entities[b].sensor {
+                return None;
             }
-            if me_to_you.dot(&entity.vel) > 0.0 {
-                if me_to_you.y > 0.0 {
-                    entity.pos.1 -= overlap.1;
-                } else {
-                    entity.pos.1 += overlap.1;
-                }
+            let (ea, eb) = (&entities[a], &entities[b]);
+            let closing_speed = (ea.vel.clone() - eb.vel.clone()).dot(&contact.manifold.normal);
+            let bounce = restitution(ea, eb, &contact.manifold) * closing_speed;
+            Some((a, b, contact.manifold.clone(), bounce))
+        })
+        .collect();
+    if let Some(config) = config {
+        pairs.extend(lingering(entities, &lookup, contacts, impulses, config));
+    }
+
+    // start from whatever impulses the same pairs needed last step
+    let mut applied: Vec<Applied> = Vec::with_capacity(pairs.len());
+    for (a, b, manifold, _) in pairs.iter() {
+        let (a, b) = (*a, *b);
+        let last = impulses
+            .applied
+            .get(&(entities[a].uuid, entities[b].uuid))
+            .map(|(_, last)| *last)
+            .unwrap_or_default();
+        let lever = Lever::new(&entities[a], &entities[b], manifold);
+        let normal = &manifold.normal;
+        let impulse = normal.clone() * last.normal + tangent(normal) * last.tangent;
+        lever.push(entities, &bodies, (a, b), &impulse);
+        if let Some(config) = config {
+            brace(entities, &bodies, a, config);
+            brace(entities, &bodies, b, config);
+        }
+        applied.push(last);
+    }
+
+    // entities can be touching several others, so the impulses are applied repeatedly
+    // to let their effects propagate through stacks and pileups.
+    for _ in 0..SOLVER_ITERATIONS {
+        for ((a, b, manifold, bounce), applied) in pairs.iter().zip(applied.iter_mut()) {
+            apply_impulse(entities, &bodies, (*a, *b), manifold, *bounce, applied);
+            if let Some(config) = config {
+                brace(entities, &bodies, *a, config);
+                brace(entities, &bodies, *b, config);
             }
         }
+    }
+    impulses.applied = pairs
+        .iter()
+        .zip(applied)
+        .map(|((a, b, manifold, _), applied)| {
+            let key = (entities[*a].uuid, entities[*b].uuid);
+            (key, (manifold.normal.clone(), applied))
+        })
+        .collect();
 
-        // colliding horizontally
-        if overlap.1 >= overlap.0 {
-            if entity.vel.x.abs() >= COLLISION_TRIGGER_VEL
-                || equivalent_single_entity.vel.x.abs() >= COLLISION_TRIGGER_VEL
-            {
-                entity.apply_force(EuclidianVector::new(collide.x, 0.0));
+    // how far each entity has been moved so far whilst separating entities,
+    // so overlaps that have already been corrected are not corrected again.
+    // overlaps are corrected a little at a time over several passes, so entities wedged between
+    // others aren't shoved back and forth by whichever of their contacts is corrected last.
+    let mut shifts: Vec<EuclidianVector> = vec![EuclidianVector::new(0.0, 0.0); entities.len()];
+    for _ in 0..SOLVER_ITERATIONS {
+        for (a, b, manifold, _) in pairs.iter() {
+            separate(entities, &bodies, (*a, *b), manifold, &mut shifts);
+        }
+    }
+}
+
+/// returns the pairs of entities that were touching last step, but have since drifted apart by
+/// less than the contact margin, with the gap between them as a negative depth, and how fast
+/// they can close that gap within a step (as a negative bounce).
+/// entities resting on several others at slightly different heights would otherwise drop in and
+/// out of touching the lower ones from step to step, and never settle.
+fn lingering(
+    entities: &[Entity],
+    lookup: &HashMap<Uuid, usize>,
+    contacts: &[Contact],
+    impulses: &Impulses,
+    config: &WorldConfig,
+) -> Vec<(usize, usize, Manifold, f32)> {
+    let touching: HashSet<(Uuid, Uuid)> = contacts
+        .iter()
+        .map(|contact| (contact.a, contact.b))
+        .collect();
+    let mut pairs: Vec<(usize, usize, Manifold, f32)> = impulses
+        .applied
+        .iter()
+        .filter(|(key, _)| !touching.contains(key))
+        .filter_map(|((a, b), (normal, _))| {
+            let (a, b) = (*lookup.get(a)?, *lookup.get(b)?);
+            let (ea, eb) = (&entities[a], &entities[b]);
+            if ea.asleep || eb.asleep || !ea.collides_with(eb) {
+                return None;
+            }
+            // pairs still overlapping weren't reported touching for some other reason
+            // (e.g. passing through a one-way entity), so are left alone
+            if narrowphase(ea, eb).is_some() {
+                return None;
             }
-            if me_to_you.dot(&entity.vel) > 0.0 {
-                if me_to_you.x > 0.0 {
-                    entity.pos.0 -= overlap.0;
-                } else {
-                    entity.pos.0 += overlap.0;
+            let mut nearer = eb.clone();
+            nearer.pos.0 -= normal.x * CONTACT_MARGIN;
+            nearer.pos.1 -= normal.y * CONTACT_MARGIN;
+            let mut manifold = narrowphase(ea, &nearer)?.manifold;
+            // pairs now touching some other way round are left to touch that way next step
+            if manifold.normal.dot(normal) < 0.99 {
+                return None;
+            }
+            // (entities can overlap slightly without touching, see `DEADZONE`)
+            manifold.depth -= CONTACT_MARGIN;
+            let bounce = manifold.depth.min(0.0) / config.time_step;
+            Some((a, b, manifold, bounce))
+        })
+        .collect();
+    // the impulses are applied in the same order on every run
+    pairs.sort_unstable_by_key(|&(a, b, _, _)| (a, b));
+    pairs
+}
+
+/// stops the entity's body moving out through any edge of the world that it is resting against,
+/// so the edge holds up whatever the body is holding up.
+fn brace(entities: &mut [Entity], bodies: &Bodies, idx: usize, config: &WorldConfig) {
+    if bodies.inverse_mass(idx) <= 0.0 {
+        return;
+    }
+    let mut vel = entities[idx].vel.clone();
+    for &member in bodies.members(idx) {
+        let (x, y) = entities[member].edges_touched(config);
+        if vel.x * x > 0.0 {
+            vel.x = 0.0;
+        }
+        if vel.y * y > 0.0 {
+            vel.y = 0.0;
+        }
+    }
+    for &member in bodies.members(idx) {
+        entities[member].vel = vel.clone();
+    }
+}
+
+/// Moves each bullet back to where it first touched a static entity on its way from its start
+/// position, and bounces it off that entity, so it can't tunnel through walls thinner than
+/// the distance it travels in one step (See "swept AABB", Ericson Ch5).
+/// Bullets that start the step already touching a wall are hit immediately, and only lose
+/// the part of their movement heading into the wall, so they can still slide along it.
+/// Bullets that start the step inside a one-way wall are passing through it, so are left alone.
+pub fn sweep(entities: &mut [Entity], starts: &[(f32, f32)]) {
+    if !entities.iter().any(|entity| entity.bullet) {
+        return;
+    }
+    let grid = SpatialHash::new(entities);
+
+    for (idx, start) in starts.iter().enumerate() {
+        let bullet = &entities[idx];
+        if !bullet.bullet || bullet.body.is_some() || bullet.immovable() {
+            continue;
+        }
+        let path = EuclidianVector::from(*start, bullet.pos);
+        let (direction, length) = (path.unit(), path.magnitude());
+
+        // only the walls near the bullet's path could be in its way
+        let (w, h) = bullet.half_extents();
+        let min = (start.0.min(bullet.pos.0) - w, start.1.min(bullet.pos.1) - h);
+        let max = (start.0.max(bullet.pos.0) + w, start.1.max(bullet.pos.1) + h);
+
+        // the first static entity along the bullet's path
+        let mut first: Option<(usize, f32, EuclidianVector)> = None;
+        for other in grid.within(min, max) {
+            let wall = &entities[other];
+            if wall.id != EntityType::Static || wall.sensor || !bullet.collides_with(wall) {
+                continue;
+            }
+            let Some((distance, normal)) = time_of_impact(bullet, start, &direction, wall) else {
+                continue;
+            };
+            if distance > length {
+                continue;
+            }
+            let normal = if distance <= 0.0 {
+                // already touching, so push back out the way the wall would
+                let mut touching = bullet.clone();
+                touching.pos = *start;
+                match narrowphase(wall, &touching) {
+                    Some(_) if wall.one_way.is_some() => continue,
+                    Some(contact) => contact.manifold.normal,
+                    None => normal,
                 }
+            } else {
+                normal
+            };
+            // one-way walls only stop bullets hitting their open side
+            if wall
+                .one_way
+                .as_ref()
+                .is_some_and(|side| normal.dot(side) <= 0.0)
+            {
+                continue;
+            }
+            if first
+                .as_ref()
+                .is_none_or(|(_, nearest, _)| distance.max(0.0) < *nearest)
+            {
+                first = Some((other, distance.max(0.0), normal));
             }
         }
+        let Some((other, distance, normal)) = first else {
+            continue;
+        };
+
+        // stop at the wall, and lose (some of) the velocity heading into it
+        let manifold = Manifold {
+            normal: normal.clone() * -1.0,
+            depth: 0.0,
+            points: Vec::new(),
+        };
+        let e = restitution(&entities[idx], &entities[other], &manifold);
+        let bullet = &mut entities[idx];
+        bullet.pos = if distance > 0.0 {
+            (
+                start.0 + direction.x * distance,
+                start.1 + direction.y * distance,
+            )
+        } else {
+            // keep any movement along (or away from) the wall
+            let into = path.dot(&normal).min(0.0);
+            (
+                bullet.pos.0 - normal.x * into,
+                bullet.pos.1 - normal.y * into,
+            )
+        };
+        let closing_speed = bullet.vel.dot(&normal);
+        if closing_speed < 0.0 {
+            bullet.vel -= normal.clone() * ((1.0 + e) * closing_speed);
+        }
+        if normal.y < -0.5 {
+            bullet.grounded = true;
+        }
+    }
+}
+
+/// returns how far a bullet travels from its start position until it first touches the wall,
+/// along with the wall's surface normal at that point.
+/// the wall's hitbox is grown by the size of the bullet, so only the bullet's centroid is traced.
+fn time_of_impact(
+    bullet: &Entity,
+    start: &(f32, f32),
+    direction: &EuclidianVector,
+    wall: &Entity,
+) -> Option<(f32, EuclidianVector)> {
+    let (w, h) = bullet.half_extents();
+    match &wall.hitbox {
+        Hitbox::Square | Hitbox::Aabb { .. } => {
+            let (wall_w, wall_h) = wall.half_extents();
+            Aabb::new(&wall.pos, wall_w + w, wall_h + h).raycast(start, direction)
+        }
+        Hitbox::Circle => {
+            let radius = wall.hit_radius + w.max(h);
+            Circle::new(&wall.pos, &radius).raycast(start, direction)
+        }
+        Hitbox::Polygon(_) => {
+            // back off from where the centroid would meet the wall,
+            // by as far as the bullet reaches out towards the wall
+            let (distance, normal) = Shape::of(wall).raycast(start, direction)?;
+            let reach = w * normal.x.abs() + h * normal.y.abs();
+            let facing = -direction.dot(&normal);
+            if facing <= 0.0 {
+                return None;
+            }
+            Some((distance - reach / facing, normal))
+        }
+    }
+}
+
+/// returns the coefficient of restitution used for a collision between two entities,
+/// the bouncier of the two entities wins, but slow collisions are always inelastic
+/// so resting entities don't jitter.
+fn restitution(a: &Entity, b: &Entity, manifold: &Manifold) -> f32 {
+    let closing_speed = (a.vel.clone() - b.vel.clone()).dot(&manifold.normal);
+    if closing_speed < RESTITUTION_TRIGGER_VEL {
+        0.0
+    } else {
+        a.restitution.max(b.restitution)
     }
 }
 
-impl Entity {
-    /// Force generated due to velocity changes during a collision.
-    fn collision_force(&mut self, target: &Entity) -> Option<EuclidianVector> {
-        // where are we relative to one another?
-        let me_to_you = EuclidianVector::from(self.pos, target.pos).unit();
-        let you_to_me = EuclidianVector::from(target.pos, self.pos).unit();
+/// returns the direction across the contact normal, that friction acts along.
+fn tangent(normal: &EuclidianVector) -> EuclidianVector {
+    normal.perp(1.0)
+}
+
+/// The point where two entities touch, as seen from each of them.
+struct Lever {
+    /// offset of the contact point from the centroid of 'a'
+    arm_a: EuclidianVector,
+    /// offset of the contact point from the centroid of 'b'
+    arm_b: EuclidianVector,
+}
+
+impl Lever {
+    fn new(a: &Entity, b: &Entity, manifold: &Manifold) -> Lever {
+        // the average of all the contact points
+        let count = manifold.points.len().max(1) as f32;
+        let point = manifold
+            .points
+            .iter()
+            .fold((0.0, 0.0), |(x, y), p| (x + p.0 / count, y + p.1 / count));
+        let point = if manifold.points.is_empty() {
+            a.pos
+        } else {
+            point
+        };
+
+        Lever {
+            arm_a: EuclidianVector::from(a.pos, point),
+            arm_b: EuclidianVector::from(b.pos, point),
+        }
+    }
+
+    /// returns the velocity of 'a' relative to 'b' at the contact point,
+    /// including the contribution from both entities spinning.
+    fn relative_vel(&self, a: &Entity, b: &Entity) -> EuclidianVector {
+        (a.vel.clone() + self.arm_a.perp(a.angular_vel))
+            - (b.vel.clone() + self.arm_b.perp(b.angular_vel))
+    }
 
-        // are both our trajectories either orthogonal to or in the opposite direction of one-another?
-        // if so, then we're NOT moving further into the collision, so there's no velocity changes.
-        if self.vel.dot(&me_to_you) <= 0.0 && target.vel.dot(&you_to_me) <= 0.0 {
-            return None;
+    /// returns the impulse needed to change the relative velocity at the contact point by one unit
+    /// along the given direction, accounting for both linear and rotational inertia.
+    fn effective_mass(
+        &self,
+        entities: &[Entity],
+        bodies: &Bodies,
+        (a, b): (usize, usize),
+        direction: &EuclidianVector,
+    ) -> f32 {
+        let arm_a = self.arm_a.cross(direction);
+        let arm_b = self.arm_b.cross(direction);
+        let inverse = bodies.inverse_mass(a)
+            + bodies.inverse_mass(b)
+            + arm_a * arm_a * bodies.inverse_inertia(entities, a)
+            + arm_b * arm_b * bodies.inverse_inertia(entities, b);
+        if inverse <= 0.0 {
+            0.0
+        } else {
+            1.0 / inverse
         }
+    }
 
-        // what forces do I experience due to velocity changes after colliding?
-        let resultant_vel = collision_calc(
-            &EuclidianVector::new(
-                me_to_you.x.abs() * self.vel.x,
-                me_to_you.y.abs() * self.vel.y,
-            ),
-            &self.mass,
-            &EuclidianVector::new(
-                you_to_me.x.abs() * target.vel.x,
-                you_to_me.y.abs() * target.vel.y,
-            ),
-            &target.mass,
+    /// applies equal and opposite impulses at the contact point,
+    /// pushing 'a' against the given direction and 'b' along it.
+    fn push(
+        &self,
+        entities: &mut [Entity],
+        bodies: &Bodies,
+        (a, b): (usize, usize),
+        impulse: &EuclidianVector,
+    ) {
+        let (inv_inertia_a, inv_inertia_b) = (
+            bodies.inverse_inertia(entities, a),
+            bodies.inverse_inertia(entities, b),
         );
-        Some(self.target_vel(resultant_vel.x, resultant_vel.y))
+        bodies.accelerate(entities, a, &(impulse.clone() * -bodies.inverse_mass(a)));
+        bodies.accelerate(entities, b, &(impulse.clone() * bodies.inverse_mass(b)));
+        entities[a].angular_vel -= self.arm_a.cross(impulse) * inv_inertia_a;
+        entities[b].angular_vel += self.arm_b.cross(impulse) * inv_inertia_b;
+    }
+}
+
+/// changes the velocities of two touching entities along the contact normal,
+/// so they stop moving further into one another (and bounce apart, if they collided).
+/// off-center contacts also change how fast the entities are spinning.
+fn apply_impulse(
+    entities: &mut [Entity],
+    bodies: &Bodies,
+    (a, b): (usize, usize),
+    manifold: &Manifold,
+    bounce: f32,
+    applied: &mut Applied,
+) {
+    let normal = &manifold.normal;
+    let lever = Lever::new(&entities[a], &entities[b], manifold);
+
+    // how fast is 'a' closing in on 'b' along the normal?
+    let closing_speed = lever.relative_vel(&entities[a], &entities[b]).dot(normal);
+
+    // equal and opposite impulses, scaled by the inverse mass of each entity.
+    // contacts can only ever push the entities apart, so an impulse pulling them together
+    // can at most take back what has already been applied this step.
+    let impulse = (closing_speed + bounce) * lever.effective_mass(entities, bodies, (a, b), normal);
+    let impulse = (applied.normal + impulse).max(0.0) - applied.normal;
+    applied.normal += impulse;
+    lever.push(entities, bodies, (a, b), &(normal.clone() * impulse));
+
+    apply_friction(entities, bodies, (a, b), &lever, normal, applied);
+}
+
+/// opposes the entities sliding past one another along the contact tangent,
+/// the friction impulse is limited by how hard the entities are pressed together (Coulomb's law).
+fn apply_friction(
+    entities: &mut [Entity],
+    bodies: &Bodies,
+    (a, b): (usize, usize),
+    lever: &Lever,
+    normal: &EuclidianVector,
+    applied: &mut Applied,
+) {
+    // how fast is 'a' sliding past 'b'?
+    let tangent = tangent(normal);
+    let sliding_speed = lever.relative_vel(&entities[a], &entities[b]).dot(&tangent);
+
+    // the impulse needed to stop the entities sliding altogether
+    let stopping_impulse = sliding_speed * lever.effective_mass(entities, bodies, (a, b), &tangent);
+
+    // combine the friction coefficients of both entities
+    let (ea, eb) = (&entities[a], &entities[b]);
+    let static_friction = (ea.static_friction * eb.static_friction).sqrt();
+    let kinetic_friction = (ea.kinetic_friction * eb.kinetic_friction).sqrt();
+
+    // static friction holds the entities together if it can, otherwise they slide
+    let total = applied.tangent + stopping_impulse;
+    let total = if total.abs() <= static_friction * applied.normal {
+        total
+    } else {
+        total.signum() * kinetic_friction * applied.normal
+    };
+    let impulse = total - applied.tangent;
+    applied.tangent = total;
+    lever.push(entities, bodies, (a, b), &(tangent * impulse));
+}
+
+/// moves two overlapping entities apart along the contact normal,
+/// the lighter entity is moved further, and immovable entities are not moved at all.
+fn separate(
+    entities: &mut [Entity],
+    bodies: &Bodies,
+    (a, b): (usize, usize),
+    manifold: &Manifold,
+    shifts: &mut [EuclidianVector],
+) {
+    let (inv_mass_a, inv_mass_b) = (bodies.inverse_mass(a), bodies.inverse_mass(b));
+    if inv_mass_a + inv_mass_b <= 0.0 {
+        return;
+    }
+    let Manifold { normal, depth, .. } = manifold;
+
+    // discount any separation gained from resolving earlier contacts
+    let separated = (shifts[b].clone() - shifts[a].clone()).dot(normal);
+
+    // only correct part of the overlap, beyond some slop, on each pass to avoid overshooting
+    let correction = CORRECTION_RATIO * (depth - separated - CORRECTION_SLOP).max(0.0)
+        / (inv_mass_a + inv_mass_b);
+    let shift_a = normal.clone() * (-correction * inv_mass_a);
+    let shift_b = normal.clone() * (correction * inv_mass_b);
+    bodies.translate(entities, a, shift_a.x, shift_a.y);
+    bodies.translate(entities, b, shift_b.x, shift_b.y);
+    for &member in bodies.members(a) {
+        shifts[member] += shift_a.clone();
+    }
+    for &member in bodies.members(b) {
+        shifts[member] += shift_b.clone();
+    }
+
+    // whichever entity is on top is resting on the other
+    let resting = if normal.y > 0.5 {
+        Some(a)
+    } else if normal.y < -0.5 {
+        Some(b)
+    } else {
+        None
+    };
+    if let Some(idx) = resting {
+        for &member in bodies.members(idx) {
+            entities[member].grounded = true;
+        }
     }
 }
 
 /// calculate resultant velocity after colliding with a target
+/// (the same velocities `resolve` leaves perfectly elastic entities with, colliding head-on)
 /// >> conservation of kinetic energy:
 /// > > 0.5*m1*v_1a^2 + 0.5*m2*v_2a^2 = 0.5*m1*v_1b^2 + 0.5*m2*v_2b^2
 /// >> conservation of momentum :
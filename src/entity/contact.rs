@@ -0,0 +1,42 @@
+use uuid::Uuid;
+
+use super::vector::EuclidianVector;
+
+// -------------------------------------------------------------------------- //
+// ----------------------- CONTACTS BETWEEN SHAPES -------------------------- //
+// -------------------------------------------------------------------------- //
+
+/// Describes how two overlapping shapes are touching one another.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Manifold {
+    /// unit-vector directed from the first shape towards the second shape,
+    /// moving the first shape against the normal separates the two shapes.
+    pub normal: EuclidianVector,
+
+    /// how far the shapes overlap along the normal.
+    pub depth: f32,
+
+    /// the point(s) where the shapes are touching.
+    pub points: Vec<(f32, f32)>,
+}
+
+impl Manifold {
+    /// returns the same manifold, but with the normal directed towards the first shape.
+    pub fn flip(mut self) -> Manifold {
+        self.normal *= -1.0;
+        self
+    }
+}
+
+/// Describes how two overlapping entities are touching one another.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contact {
+    /// the entity the normal is directed away from
+    pub a: Uuid,
+
+    /// the entity the normal is directed towards
+    pub b: Uuid,
+
+    /// the geometry of the contact between the two entities
+    pub manifold: Manifold,
+}
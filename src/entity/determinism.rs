@@ -0,0 +1,334 @@
+use std::{collections::HashMap, hash::Hasher};
+
+use uuid::Uuid;
+
+use super::{
+    Anchor, Easing, Entity, EntityState, EntityType, Hitbox, Joint, JointKind, ParticleSystem,
+    Path, PathMode, PhysicsWorld,
+};
+
+// -------------------------------------------------------------------------- //
+// ------------------- REPEATABLE RUNS, RECORDING AND REPLAY ---------------- //
+// -------------------------------------------------------------------------- //
+
+/// Generates a repeatable sequence of (random-looking) numbers from a seed.
+#[derive(Clone, Debug)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> SeededRng {
+        SeededRng { state: seed }
+    }
+
+    /// returns the next number in the sequence (See "SplitMix64", Steele et al.)
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// returns the next number in the sequence, scaled to lie between the limits.
+    pub fn range(&mut self, lower: f32, upper: f32) -> f32 {
+        // the top 24 bits fill an f32's mantissa exactly, giving 0.0 <= fraction < 1.0
+        let fraction = (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32;
+        lower + (upper - lower) * fraction
+    }
+}
+
+/// Generates a repeatable sequence of (random-looking) uuids from a seed,
+/// so entities spawned in the same order are given the same uuids on every run.
+#[derive(Clone, Debug)]
+pub struct SeededIds {
+    rng: SeededRng,
+}
+
+impl SeededIds {
+    pub fn new(seed: u64) -> SeededIds {
+        SeededIds {
+            rng: SeededRng::new(seed),
+        }
+    }
+}
+
+impl Iterator for SeededIds {
+    type Item = Uuid;
+
+    fn next(&mut self) -> Option<Uuid> {
+        let mut bytes = [0; 16];
+        bytes[..8].copy_from_slice(&self.rng.next_u64().to_le_bytes());
+        bytes[8..].copy_from_slice(&self.rng.next_u64().to_le_bytes());
+        Some(uuid::Builder::from_random_bytes(bytes).into_uuid())
+    }
+}
+
+/// Hashes the exact (bit-for-bit) state of a world, so two runs can be checked for divergence.
+/// Unlike the standard library's hashers, the result is the same on every run (See "FNV-1a").
+struct Checksum(u64);
+
+impl Hasher for Checksum {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01B3);
+        }
+    }
+}
+
+impl Checksum {
+    fn new() -> Checksum {
+        Checksum(0xCBF2_9CE4_8422_2325)
+    }
+
+    fn write_f32s(&mut self, values: &[f32]) {
+        for value in values {
+            self.write_u32(value.to_bits());
+        }
+    }
+
+    /// writes every entity in turn, leaving out their uuids (which are random, unless spawned
+    /// into a seeded world). the parts of a compound body are tied together by the index of
+    /// the body's first part instead.
+    fn write_entities(&mut self, entities: &[Entity]) {
+        let mut bodies: HashMap<Uuid, usize> = HashMap::new();
+        self.write_usize(entities.len());
+        for (idx, entity) in entities.iter().enumerate() {
+            let body = entity.body.map(|body| *bodies.entry(body).or_insert(idx));
+            self.write_entity(entity);
+            self.write_usize(body.unwrap_or(usize::MAX));
+        }
+    }
+
+    fn write_entity(&mut self, entity: &Entity) {
+        self.write_u8(match entity.id {
+            EntityType::Npc => 0,
+            EntityType::Player => 1,
+            EntityType::Static => 2,
+            EntityType::Kinematic => 3,
+        });
+        self.write_u8(match entity.state {
+            EntityState::Alive => 0,
+            EntityState::Dead => 1,
+            EntityState::Hidden => 2,
+        });
+        self.write_f32s(&[
+            entity.pos.0,
+            entity.pos.1,
+            entity.vel.x,
+            entity.vel.y,
+            entity.acc.x,
+            entity.acc.y,
+            entity.mass,
+            entity.hit_radius,
+            entity.orientation,
+            entity.angular_vel,
+            entity.restitution,
+            entity.static_friction,
+            entity.kinetic_friction,
+            entity.input_force.x,
+            entity.input_force.y,
+            entity.torque,
+        ]);
+        match &entity.hitbox {
+            Hitbox::Square => self.write_u8(0),
+            Hitbox::Aabb {
+                half_width,
+                half_height,
+            } => {
+                self.write_u8(1);
+                self.write_f32s(&[*half_width, *half_height]);
+            }
+            Hitbox::Circle => self.write_u8(2),
+            Hitbox::Polygon(vertices) => {
+                self.write_u8(3);
+                self.write_usize(vertices.len());
+                for (x, y) in vertices {
+                    self.write_f32s(&[*x, *y]);
+                }
+            }
+        }
+        self.write(&[
+            entity.bullet as u8,
+            entity.asleep as u8,
+            entity.sensor as u8,
+            entity.grounded as u8,
+        ]);
+        self.write_u32(entity.ttl.map_or(u32::MAX, f32::to_bits));
+        match &entity.one_way {
+            Some(normal) => {
+                self.write_u8(1);
+                self.write_f32s(&[normal.x, normal.y]);
+            }
+            None => self.write_u8(0),
+        }
+        match &entity.path {
+            Some(path) => {
+                self.write_u8(1);
+                self.write_path(path);
+            }
+            None => self.write_u8(0),
+        }
+        self.write_u32(entity.idle_ticks);
+        self.write_u32(entity.category);
+        self.write_u32(entity.mask);
+    }
+
+    fn write_path(&mut self, path: &Path) {
+        self.write_usize(path.waypoints.len());
+        for (x, y) in &path.waypoints {
+            self.write_f32s(&[*x, *y]);
+        }
+        self.write_u8(match path.mode {
+            PathMode::Once => 0,
+            PathMode::PingPong => 1,
+            PathMode::Loop => 2,
+        });
+        self.write_u8(match path.easing {
+            Easing::Linear => 0,
+            Easing::Smooth => 1,
+        });
+        self.write_f32s(&[path.speed, path.elapsed]);
+    }
+
+    /// writes every joint, referring to the entities they connect by index (like compound
+    /// bodies), or `usize::MAX` for entities that are no longer in the world.
+    fn write_joints(&mut self, joints: &[Joint], entities: &[Entity]) {
+        let indices: HashMap<Uuid, usize> = entities
+            .iter()
+            .enumerate()
+            .map(|(idx, entity)| (entity.uuid, idx))
+            .collect();
+        self.write_usize(joints.len());
+        for joint in joints {
+            for anchor in [&joint.a, &joint.b] {
+                match anchor {
+                    Anchor::Entity(uuid, (x, y)) => {
+                        self.write_u8(0);
+                        self.write_usize(indices.get(uuid).copied().unwrap_or(usize::MAX));
+                        self.write_f32s(&[*x, *y]);
+                    }
+                    Anchor::Point((x, y)) => {
+                        self.write_u8(1);
+                        self.write_f32s(&[*x, *y]);
+                    }
+                }
+            }
+            match joint.kind {
+                JointKind::Distance { length } => {
+                    self.write_u8(0);
+                    self.write_f32s(&[length]);
+                }
+                JointKind::Rope { length } => {
+                    self.write_u8(1);
+                    self.write_f32s(&[length]);
+                }
+                JointKind::Revolute => self.write_u8(2),
+                JointKind::Spring {
+                    rest_length,
+                    stiffness,
+                    damping,
+                } => {
+                    self.write_u8(3);
+                    self.write_f32s(&[rest_length, stiffness, damping]);
+                }
+            }
+        }
+    }
+
+    /// writes every particle, along with where the particles' random numbers are up to,
+    /// since that decides how every particle yet to be emitted will move.
+    fn write_particles(&mut self, particles: &ParticleSystem) {
+        self.write_u64(particles.rng.state);
+        self.write_f32s(&particles.owed);
+        self.write_usize(particles.particles().len());
+        for particle in particles.particles() {
+            self.write_f32s(&[
+                particle.pos.0,
+                particle.pos.1,
+                particle.vel.x,
+                particle.vel.y,
+                particle.age,
+                particle.lifetime,
+            ]);
+        }
+    }
+}
+
+/// returns a checksum of the state of every entity, in the order they are stored.
+/// identical states always give identical checksums, on every run,
+/// whatever uuids the entities were given.
+pub fn checksum(entities: &[Entity]) -> u64 {
+    let mut checksum = Checksum::new();
+    checksum.write_entities(entities);
+    checksum.finish()
+}
+
+/// returns a checksum of the state of a world's entities, joints and particles (see `checksum`).
+pub(crate) fn world_checksum(world: &PhysicsWorld) -> u64 {
+    let mut checksum = Checksum::new();
+    checksum.write_entities(&world.entities);
+    checksum.write_joints(&world.joints, &world.entities);
+    checksum.write_particles(&world.particles);
+    checksum.finish()
+}
+
+/// The inputs applied to a world before each of its steps, and the world's checksum after it.
+/// Replaying the same inputs against the same starting world (with the same seed, see
+/// `WorldConfig::seed`) must reproduce every checksum exactly.
+#[derive(Clone, Debug, Default)]
+pub struct Recording<I> {
+    pub ticks: Vec<(I, u64)>,
+}
+
+/// The first tick at which a replay differed from its recording.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    pub tick: usize,
+    pub expected: u64,
+    pub actual: u64,
+}
+
+impl<I> Recording<I> {
+    pub fn new() -> Recording<I> {
+        Recording { ticks: Vec::new() }
+    }
+
+    /// applies an input to the world, steps the world, and records both the input
+    /// and the resulting checksum.
+    pub fn record<F>(&mut self, world: &mut PhysicsWorld, input: I, apply: F)
+    where
+        F: FnOnce(&mut PhysicsWorld, &I),
+    {
+        apply(world, &input);
+        world.step();
+        self.ticks.push((input, world.checksum()));
+    }
+
+    /// applies every recorded input to the world in turn, stepping the world after each,
+    /// and returns the first tick where the world's checksum differs from the recording.
+    pub fn replay<F>(&self, world: &mut PhysicsWorld, mut apply: F) -> Result<(), Divergence>
+    where
+        F: FnMut(&mut PhysicsWorld, &I),
+    {
+        for (tick, (input, expected)) in self.ticks.iter().enumerate() {
+            apply(world, input);
+            world.step();
+            let actual = world.checksum();
+            if actual != *expected {
+                return Err(Divergence {
+                    tick,
+                    expected: *expected,
+                    actual,
+                });
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::{BTreeMap, HashMap};
+
+use uuid::Uuid;
+
+use super::collision::narrowphase;
+use super::contact::Contact;
+use super::sleep::at_rest;
+use super::Entity;
+
+// -------------------------------------------------------------------------- //
+// ------------------------ CONTACT EVENT STREAM ---------------------------- //
+// -------------------------------------------------------------------------- //
+
+/// how far entities can drift apart (along their last contact normal) whilst still touching,
+/// so entities resting on one another, which dip in and out of overlapping from step to step,
+/// aren't reported as stopping and starting touching over and over
+/// (nor dropped back onto one another by the solver, see `collision::resolve_warm`).
+pub(crate) const CONTACT_MARGIN: f32 = 0.05;
+
+/// Whether a pair of entities started touching, kept touching or stopped touching during a step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContactPhase {
+    Begin,
+    Stay,
+    End,
+}
+
+/// Tells game code that a pair of entities touched during a step
+/// (e.g. to pick something up, or to score when entering a goal zone).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContactEvent {
+    pub phase: ContactPhase,
+
+    /// how the entities are touching, or how they were last touching for `End` events.
+    pub contact: Contact,
+}
+
+impl ContactEvent {
+    /// returns true if the entity is either of the pair that touched.
+    pub fn involves(&self, uuid: Uuid) -> bool {
+        self.contact.a == uuid || self.contact.b == uuid
+    }
+
+    /// returns the entity that the given entity touched, if it is one of the pair.
+    pub fn other(&self, uuid: Uuid) -> Option<Uuid> {
+        if self.contact.a == uuid {
+            Some(self.contact.b)
+        } else if self.contact.b == uuid {
+            Some(self.contact.a)
+        } else {
+            None
+        }
+    }
+}
+
+/// Remembers which pairs of entities were touching after the last step,
+/// so each step's contacts can be turned into events.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ContactTracker {
+    touching: BTreeMap<(Uuid, Uuid), Contact>,
+}
+
+impl ContactTracker {
+    /// returns an event for every pair of entities that is touching this step,
+    /// and every pair that was touching last step but no longer is.
+    pub(crate) fn update(
+        &mut self,
+        entities: &[Entity],
+        contacts: &[Contact],
+    ) -> Vec<ContactEvent> {
+        let lookup: HashMap<Uuid, &Entity> = entities
+            .iter()
+            .map(|entity| (entity.uuid, entity))
+            .collect();
+        let mut events: Vec<ContactEvent> =
+            Vec::with_capacity(contacts.len() + self.touching.len());
+
+        // nothing new touching (e.g. with every entity asleep), so only the pairs that were
+        // touching need checking, in place
+        if contacts.is_empty() {
+            self.touching.retain(|_, contact| {
+                let still_touching = match (lookup.get(&contact.a), lookup.get(&contact.b)) {
+                    (Some(a), Some(b)) => touching_within_margin(a, b, contact),
+                    _ => false,
+                };
+                let phase = if still_touching {
+                    ContactPhase::Stay
+                } else {
+                    ContactPhase::End
+                };
+                events.push(ContactEvent {
+                    phase,
+                    contact: contact.clone(),
+                });
+                still_touching
+            });
+            return events;
+        }
+
+        let mut touching: BTreeMap<(Uuid, Uuid), Contact> = BTreeMap::new();
+        for contact in contacts {
+            let phase = match self.touching.remove(&pair(contact)) {
+                Some(_) => ContactPhase::Stay,
+                None => ContactPhase::Begin,
+            };
+            events.push(ContactEvent {
+                phase,
+                contact: contact.clone(),
+            });
+            touching.insert(pair(contact), contact.clone());
+        }
+
+        for (key, contact) in std::mem::take(&mut self.touching) {
+            let still_touching = match (lookup.get(&contact.a), lookup.get(&contact.b)) {
+                (Some(a), Some(b)) => touching_within_margin(a, b, &contact),
+                _ => false,
+            };
+            let phase = if still_touching {
+                touching.insert(key, contact.clone());
+                ContactPhase::Stay
+            } else {
+                ContactPhase::End
+            };
+            events.push(ContactEvent { phase, contact });
+        }
+
+        self.touching = touching;
+        events
+    }
+}
+
+/// returns true if a pair of entities that were touching are still close enough to be touching.
+fn touching_within_margin(a: &Entity, b: &Entity, contact: &Contact) -> bool {
+    if !a.collides_with(b) {
+        return false;
+    }
+    // pairs that are at rest aren't checked against one another, but can't have moved apart
+    if at_rest(a) && at_rest(b) {
+        return true;
+    }
+    let mut nearer = b.clone();
+    let normal = &contact.manifold.normal;
+    nearer.pos.0 -= normal.x * CONTACT_MARGIN;
+    nearer.pos.1 -= normal.y * CONTACT_MARGIN;
+    narrowphase(a, &nearer).is_some()
+}
+
+/// returns the uuids of a contact's entities in the same order, whichever way round they touched.
+fn pair(contact: &Contact) -> (Uuid, Uuid) {
+    (contact.a.min(contact.b), contact.a.max(contact.b))
+}
@@ -0,0 +1,115 @@
+use super::vector::EuclidianVector;
+use super::{Bounds, Entity, WorldConfig};
+
+// -------------------------------------------------------------------------- //
+// --------------------- REUSABLE FORCE FIELDS ------------------------------ //
+// -------------------------------------------------------------------------- //
+
+/// Something that pushes on entities every step, e.g. wind or drag.
+/// Generators are registered with a world (see `PhysicsWorld::add_generator`),
+/// which applies them to every entity, or just those passing a filter,
+/// waking any asleep entity they push on.
+pub trait ForceGenerator {
+    /// returns the force to apply to the entity in its current state.
+    /// may be called several times a step, with the entity part-way through the step,
+    /// depending on the world's integrator (see `Integrator`).
+    fn force(&self, entity: &Entity, config: &WorldConfig) -> EuclidianVector;
+}
+
+/// A uniform acceleration, on top of the world's own gravity
+/// (e.g. reversed gravity for some entities, or a sideways pull).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gravity(pub EuclidianVector);
+
+impl ForceGenerator for Gravity {
+    fn force(&self, entity: &Entity, _: &WorldConfig) -> EuclidianVector {
+        self.0.clone() * entity.mass
+    }
+}
+
+/// Resistance to moving through the air (or anything else), opposing the entity's velocity:
+/// F = -v * (linear + quadratic * |v|)
+/// linear drag dominates at low speeds, quadratic drag at high speeds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Drag {
+    pub linear: f32,
+    pub quadratic: f32,
+}
+
+impl ForceGenerator for Drag {
+    fn force(&self, entity: &Entity, _: &WorldConfig) -> EuclidianVector {
+        let speed = entity.vel.magnitude();
+        entity.vel.clone() * -(self.linear + self.quadratic * speed)
+    }
+}
+
+/// Pulls entities towards a point, more strongly the closer they are (inverse-square law).
+/// A negative strength pushes entities away instead.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PointAttractor {
+    pub point: (f32, f32),
+    pub strength: f32,
+
+    /// entities closer than this are pulled as if they were this far away,
+    /// so the pull doesn't become infinite at the point itself.
+    pub min_distance: f32,
+}
+
+impl ForceGenerator for PointAttractor {
+    fn force(&self, entity: &Entity, _: &WorldConfig) -> EuclidianVector {
+        let offset = EuclidianVector::from(entity.pos, self.point);
+        let distance = offset.magnitude();
+        if distance == 0.0 {
+            return EuclidianVector::new(0.0, 0.0);
+        }
+        let distance = distance.max(self.min_distance);
+        offset.unit() * (self.strength * entity.mass / (distance * distance))
+    }
+}
+
+/// Moving air, which drags entities along until they're moving with it:
+/// F = coefficient * (wind - v)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Wind {
+    pub vel: EuclidianVector,
+    pub coefficient: f32,
+}
+
+impl ForceGenerator for Wind {
+    fn force(&self, entity: &Entity, _: &WorldConfig) -> EuclidianVector {
+        (self.vel.clone() - entity.vel.clone()) * self.coefficient
+    }
+}
+
+/// A region of liquid, which pushes entities against the world's gravity in proportion to how
+/// much liquid they displace (Archimedes' principle), and slows entities moving through it.
+/// Entities lighter than the liquid they'd displace float, heavier entities sink.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Buoyancy {
+    pub region: Bounds,
+
+    /// mass of liquid per unit area.
+    pub density: f32,
+
+    /// linear drag on entities that are fully submerged.
+    pub drag: f32,
+}
+
+impl ForceGenerator for Buoyancy {
+    fn force(&self, entity: &Entity, config: &WorldConfig) -> EuclidianVector {
+        // the area of the entity's bounding box that is within the liquid
+        let (w, h) = entity.half_extents();
+        let overlap =
+            |min: f32, max: f32, lower: f32, upper: f32| (max.min(upper) - min.max(lower)).max(0.0);
+        let (x, y) = entity.pos;
+        let submerged = overlap(x - w, x + w, self.region.left, self.region.right)
+            * overlap(y - h, y + h, self.region.top, self.region.bottom);
+        if submerged <= 0.0 {
+            return EuclidianVector::new(0.0, 0.0);
+        }
+        let fraction = submerged / (4.0 * w * h);
+
+        config.gravity.clone() * -(self.density * submerged)
+            - entity.vel.clone() * (self.drag * fraction)
+    }
+}
@@ -0,0 +1,160 @@
+use std::ops::{Add, Mul};
+
+use super::vector::EuclidianVector;
+
+// -------------------------------------------------------------------------- //
+// ----------------- NUMERICAL INTEGRATION OF ENTITY MOTION ----------------- //
+// -------------------------------------------------------------------------- //
+
+/// The scheme used to advance positions and velocities by a time step,
+/// trading accuracy against the number of times the acceleration is evaluated.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Integrator {
+    /// velocity is advanced first, then position using the new velocity and the acceleration:
+    /// v1 = v0 + at, x1 = x0 + v1t + 0.5at^2
+    #[default]
+    Explicit,
+    /// velocity is advanced first, then position using the new velocity:
+    /// v1 = v0 + at, x1 = x0 + v1t
+    /// cheap, and energy stays bounded for oscillating systems (See "symplectic Euler").
+    SemiImplicitEuler,
+    /// position is advanced using the current acceleration, then velocity using the average
+    /// of the accelerations at the start and end of the step. exact for constant forces.
+    VelocityVerlet,
+    /// classic fourth-order Runge-Kutta, evaluating the acceleration four times per step.
+    /// the most accurate over short time-scales, at the most cost.
+    RungeKutta4,
+}
+
+impl Integrator {
+    /// returns the position and velocity after a time step, starting from some position
+    /// and velocity, and accelerating as given by a function of position and velocity.
+    pub fn integrate<F>(
+        &self,
+        pos: (f32, f32),
+        vel: &EuclidianVector,
+        dt: f32,
+        acceleration: F,
+    ) -> ((f32, f32), EuclidianVector)
+    where
+        F: Fn((f32, f32), &EuclidianVector) -> EuclidianVector,
+    {
+        self.solve(pos, vel, dt, acceleration)
+    }
+
+    /// returns the orientation and angular velocity after a time step, starting from some
+    /// orientation and angular velocity, and accelerating as given by a function of the two.
+    pub fn integrate_angle<F>(
+        &self,
+        orientation: f32,
+        angular_vel: f32,
+        dt: f32,
+        angular_acc: F,
+    ) -> (f32, f32)
+    where
+        F: Fn(f32, f32) -> f32,
+    {
+        self.solve(orientation, &angular_vel, dt, |angle, rate: &f32| {
+            angular_acc(angle, *rate)
+        })
+    }
+
+    /// advances some coordinates, and their rate of change, by a time step.
+    fn solve<C, F>(&self, pos: C, vel: &C::Rate, dt: f32, acceleration: F) -> (C, C::Rate)
+    where
+        C: Coordinates,
+        F: Fn(C, &C::Rate) -> C::Rate,
+    {
+        let still = C::still();
+
+        match self {
+            Integrator::Explicit => {
+                let acc = acceleration(pos, vel);
+                let vel = vel.clone() + acc.clone() * dt;
+                (pos.advance(&vel, &(acc * 0.5), dt), vel)
+            }
+            Integrator::SemiImplicitEuler => {
+                let vel = vel.clone() + acceleration(pos, vel) * dt;
+                (pos.advance(&vel, &still, dt), vel)
+            }
+            Integrator::VelocityVerlet => {
+                let acc = acceleration(pos, vel);
+                let next = pos.advance(vel, &(acc.clone() * 0.5), dt);
+                // the velocity at the end of the step is estimated for velocity-dependent forces
+                let guess = vel.clone() + acc.clone() * dt;
+                let next_acc = acceleration(next, &guess);
+                (next, vel.clone() + (acc + next_acc) * (0.5 * dt))
+            }
+            Integrator::RungeKutta4 => {
+                let k1_vel = vel.clone();
+                let k1_acc = acceleration(pos, &k1_vel);
+
+                let k2_vel = vel.clone() + k1_acc.clone() * (0.5 * dt);
+                let k2_acc = acceleration(pos.advance(&k1_vel, &still, 0.5 * dt), &k2_vel);
+
+                let k3_vel = vel.clone() + k2_acc.clone() * (0.5 * dt);
+                let k3_acc = acceleration(pos.advance(&k2_vel, &still, 0.5 * dt), &k3_vel);
+
+                let k4_vel = vel.clone() + k3_acc.clone() * dt;
+                let k4_acc = acceleration(pos.advance(&k3_vel, &still, dt), &k4_vel);
+
+                // weighted average of the slopes
+                let mean_vel = (k1_vel + (k2_vel + k3_vel) * 2.0 + k4_vel) * (1.0 / 6.0);
+                let mean_acc = (k1_acc + (k2_acc + k3_acc) * 2.0 + k4_acc) * (1.0 / 6.0);
+                (
+                    pos.advance(&mean_vel, &still, dt),
+                    vel.clone() + mean_acc * dt,
+                )
+            }
+        }
+    }
+
+    /// returns how far an entity is displaced in a single step by a constant acceleration,
+    /// as a multiple of (acceleration * time_step^2), on top of its displacement from its
+    /// current velocity.
+    pub fn displacement_factor(&self) -> f32 {
+        match self {
+            Integrator::Explicit => 1.5,
+            Integrator::SemiImplicitEuler => 1.0,
+            Integrator::VelocityVerlet | Integrator::RungeKutta4 => 0.5,
+        }
+    }
+}
+
+/// Coordinates that change at some rate, e.g. a position moving with some velocity,
+/// or an orientation turning with some angular velocity.
+trait Coordinates: Copy {
+    type Rate: Clone + Add<Output = Self::Rate> + Mul<f32, Output = Self::Rate>;
+
+    /// returns a rate of no change at all.
+    fn still() -> Self::Rate;
+
+    /// returns the coordinates after changing at some rate, and (optionally) accelerating,
+    /// for some time.
+    fn advance(self, rate: &Self::Rate, acc: &Self::Rate, t: f32) -> Self;
+}
+
+impl Coordinates for (f32, f32) {
+    type Rate = EuclidianVector;
+
+    fn still() -> EuclidianVector {
+        EuclidianVector::new(0.0, 0.0)
+    }
+
+    fn advance(self, v: &EuclidianVector, a: &EuclidianVector, t: f32) -> (f32, f32) {
+        let (x, y) = self;
+        (x + (v.x * t + a.x * t * t), y + (v.y * t + a.y * t * t))
+    }
+}
+
+impl Coordinates for f32 {
+    type Rate = f32;
+
+    fn still() -> f32 {
+        0.0
+    }
+
+    fn advance(self, w: &f32, a: &f32, t: f32) -> f32 {
+        self + (w * t + a * t * t)
+    }
+}
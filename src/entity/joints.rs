@@ -0,0 +1,399 @@
+use std::collections::{HashMap, HashSet};
+
+use uuid::Uuid;
+
+use super::rigid_body::Bodies;
+use super::sleep::at_rest;
+use super::vector::EuclidianVector;
+use super::Entity;
+
+// -------------------------------------------------------------------------- //
+// -------------------- JOINTS AND CONSTRAINTS BETWEEN ENTITIES ------------- //
+// -------------------------------------------------------------------------- //
+
+/// how many times every joint is solved each step,
+/// so corrections can travel along chains of joints (e.g. a rope bridge).
+const JOINT_ITERATIONS: usize = 8;
+
+/// anchors closer than this are treated as being at the same point.
+const JOINT_EPSILON: f32 = 1e-6;
+
+/// One end of a joint.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Anchor {
+    /// a point on an entity, given relative to its centroid (and turning with the entity).
+    Entity(Uuid, (f32, f32)),
+    /// a point fixed in the world.
+    Point((f32, f32)),
+}
+
+impl Anchor {
+    /// returns an anchor at the entity's centroid.
+    pub fn centroid(entity: &Entity) -> Anchor {
+        Anchor::Entity(entity.uuid, (0.0, 0.0))
+    }
+
+    /// returns an anchor on the entity at a point in the world,
+    /// which then moves and turns along with the entity.
+    pub fn at(entity: &Entity, point: (f32, f32)) -> Anchor {
+        // undo the entity's rotation, so the offset turns with the entity
+        let (sin, cos) = (-entity.orientation).sin_cos();
+        let (x, y) = (point.0 - entity.pos.0, point.1 - entity.pos.1);
+        Anchor::Entity(entity.uuid, (x * cos - y * sin, x * sin + y * cos))
+    }
+}
+
+/// How a joint constrains its anchors.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JointKind {
+    /// keeps the anchors exactly some length apart, like a rigid rod.
+    Distance { length: f32 },
+    /// stops the anchors moving more than some length apart,
+    /// but lets them move closer together, like a rope or chain.
+    Rope { length: f32 },
+    /// keeps the anchors at the same point, but lets the entities turn about it, like a hinge.
+    Revolute,
+    /// pulls the anchors towards some length apart with a force growing with how far the spring
+    /// is stretched (or squashed), and opposing how fast it is being stretched (Hooke's law).
+    Spring {
+        rest_length: f32,
+        stiffness: f32,
+        damping: f32,
+    },
+}
+
+/// Connects two entities, or an entity and a point in the world.
+/// Joints to entities that are no longer in the world are ignored.
+/// Connected entities still collide with one another (see `Entity::mask`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Joint {
+    pub a: Anchor,
+    pub b: Anchor,
+    pub kind: JointKind,
+}
+
+impl Joint {
+    pub fn new(a: Anchor, b: Anchor, kind: JointKind) -> Joint {
+        Joint { a, b, kind }
+    }
+
+    /// creates a rigid rod between the anchors.
+    pub fn distance(a: Anchor, b: Anchor, length: f32) -> Joint {
+        Joint::new(a, b, JointKind::Distance { length })
+    }
+
+    /// creates a rope between the anchors.
+    pub fn rope(a: Anchor, b: Anchor, length: f32) -> Joint {
+        Joint::new(a, b, JointKind::Rope { length })
+    }
+
+    /// pins the entity to a point in the world, about which it can still turn.
+    pub fn revolute(entity: &Entity, point: (f32, f32)) -> Joint {
+        Joint::new(
+            Anchor::at(entity, point),
+            Anchor::Point(point),
+            JointKind::Revolute,
+        )
+    }
+
+    /// creates a damped spring between the anchors.
+    pub fn spring(a: Anchor, b: Anchor, rest_length: f32, stiffness: f32, damping: f32) -> Joint {
+        Joint::new(
+            a,
+            b,
+            JointKind::Spring {
+                rest_length,
+                stiffness,
+                damping,
+            },
+        )
+    }
+
+    /// returns the uuids of the entities the joint is connected to.
+    pub fn entities(&self) -> impl Iterator<Item = Uuid> + '_ {
+        [&self.a, &self.b]
+            .into_iter()
+            .filter_map(|anchor| match anchor {
+                Anchor::Entity(uuid, _) => Some(*uuid),
+                Anchor::Point(_) => None,
+            })
+    }
+}
+
+/// An anchor positioned in the world at the start of a solver iteration.
+struct End {
+    /// the entity the anchor is on, if any
+    idx: Option<usize>,
+    point: (f32, f32),
+    /// offset of the anchor from the entity's centroid
+    arm: EuclidianVector,
+}
+
+impl End {
+    /// returns where the anchor is, or `None` if its entity is no longer in the world.
+    fn new(anchor: &Anchor, entities: &[Entity], lookup: &HashMap<Uuid, usize>) -> Option<End> {
+        match anchor {
+            Anchor::Point(point) => Some(End {
+                idx: None,
+                point: *point,
+                arm: EuclidianVector::new(0.0, 0.0),
+            }),
+            Anchor::Entity(uuid, offset) => {
+                let idx = *lookup.get(uuid)?;
+                Some(End::on(&entities[idx], idx, *offset))
+            }
+        }
+    }
+
+    /// returns where an anchor at some offset from the entity's centroid is,
+    /// with the entity (at index `idx`) in the given state.
+    fn on(entity: &Entity, idx: usize, (x, y): (f32, f32)) -> End {
+        let (sin, cos) = entity.orientation.sin_cos();
+        let arm = EuclidianVector::new(x * cos - y * sin, x * sin + y * cos);
+        End {
+            idx: Some(idx),
+            point: (entity.pos.0 + arm.x, entity.pos.1 + arm.y),
+            arm,
+        }
+    }
+
+    /// returns the velocity of the anchor, including the contribution from its entity spinning.
+    fn vel(&self, entities: &[Entity]) -> EuclidianVector {
+        match self.idx {
+            Some(idx) => self.vel_on(&entities[idx]),
+            None => EuclidianVector::new(0.0, 0.0),
+        }
+    }
+
+    /// returns the velocity of the anchor, with its entity in the given state.
+    fn vel_on(&self, entity: &Entity) -> EuclidianVector {
+        entity.vel.clone() + self.arm.perp(entity.angular_vel)
+    }
+
+    fn inverse_mass(&self, bodies: &Bodies) -> f32 {
+        self.idx.map_or(0.0, |idx| bodies.inverse_mass(idx))
+    }
+
+    /// returns how hard it is to change the anchor's velocity along the given direction,
+    /// accounting for both linear and rotational inertia.
+    fn inverse_effective_mass(
+        &self,
+        entities: &[Entity],
+        bodies: &Bodies,
+        direction: &EuclidianVector,
+    ) -> f32 {
+        let Some(idx) = self.idx else {
+            return 0.0;
+        };
+        let arm = self.arm.cross(direction);
+        bodies.inverse_mass(idx) + arm * arm * bodies.inverse_inertia(entities, idx)
+    }
+
+    /// applies an impulse at the anchor.
+    fn push(&self, entities: &mut [Entity], bodies: &Bodies, impulse: &EuclidianVector) {
+        let Some(idx) = self.idx else {
+            return;
+        };
+        let inv_inertia = bodies.inverse_inertia(entities, idx);
+        bodies.accelerate(entities, idx, &(impulse.clone() * bodies.inverse_mass(idx)));
+        entities[idx].angular_vel += self.arm.cross(impulse) * inv_inertia;
+    }
+}
+
+/// The springs in a world, ready to work out how hard they pull on the entities attached to them.
+/// The pull changes as entities move, so can be worked out for an entity part-way through a step.
+pub(crate) struct Springs<'a> {
+    springs: Vec<&'a Joint>,
+    lookup: HashMap<Uuid, usize>,
+    /// the indices of every entity attached to a spring
+    attached: HashSet<usize>,
+}
+
+impl Springs<'_> {
+    pub(crate) fn new<'a>(entities: &[Entity], joints: &'a [Joint]) -> Springs<'a> {
+        let springs: Vec<&Joint> = joints
+            .iter()
+            .filter(|joint| matches!(joint.kind, JointKind::Spring { .. }))
+            .collect();
+        let lookup = lookup(entities);
+        let attached = springs
+            .iter()
+            .flat_map(|joint| joint.entities())
+            .filter_map(|uuid| lookup.get(&uuid).copied())
+            .collect();
+        Springs {
+            springs,
+            lookup,
+            attached,
+        }
+    }
+
+    /// returns true if the entity (at index `idx`) is attached to any spring.
+    pub(crate) fn attached(&self, idx: usize) -> bool {
+        self.attached.contains(&idx)
+    }
+
+    /// returns the force, and torque, that every spring attached to the entity (at index `idx`)
+    /// exerts on it, were the entity in the given state rather than the one it is in.
+    pub(crate) fn pull(
+        &self,
+        entities: &[Entity],
+        idx: usize,
+        state: &Entity,
+    ) -> (EuclidianVector, f32) {
+        let (mut force, mut torque) = (EuclidianVector::new(0.0, 0.0), 0.0);
+        if !self.attached(idx) {
+            return (force, torque);
+        }
+        let end = |anchor: &Anchor| match anchor {
+            Anchor::Entity(uuid, offset) if self.lookup.get(uuid) == Some(&idx) => {
+                Some(End::on(state, idx, *offset))
+            }
+            _ => End::new(anchor, entities, &self.lookup),
+        };
+        let vel = |end: &End| match end.idx {
+            Some(other) if other == idx => end.vel_on(state),
+            _ => end.vel(entities),
+        };
+        for joint in self.springs.iter() {
+            let JointKind::Spring {
+                rest_length,
+                stiffness,
+                damping,
+            } = joint.kind
+            else {
+                continue;
+            };
+            let (Some(a), Some(b)) = (end(&joint.a), end(&joint.b)) else {
+                continue;
+            };
+            if a.idx != Some(idx) && b.idx != Some(idx) {
+                continue;
+            }
+            let offset = EuclidianVector::from(a.point, b.point);
+            let length = offset.magnitude();
+            if length < JOINT_EPSILON {
+                continue;
+            }
+            let direction = offset.unit();
+
+            // stretched springs pull 'a' towards 'b', and 'b' towards 'a'
+            let stretching = (vel(&b) - vel(&a)).dot(&direction);
+            let tension = stiffness * (length - rest_length) + damping * stretching;
+            for (end, pull) in [(a, direction.clone() * tension), (b, direction * -tension)] {
+                if end.idx == Some(idx) {
+                    torque += end.arm.cross(&pull);
+                    force += pull;
+                }
+            }
+        }
+        (force, torque)
+    }
+}
+
+/// Changes the velocities of jointed entities so they stop moving against their joints,
+/// then moves them back to where their joints allow.
+/// Springs are only driven by forces (see `Springs`), so are left alone here.
+pub(crate) fn solve(entities: &mut [Entity], joints: &[Joint]) {
+    if joints.is_empty() {
+        return;
+    }
+    let lookup = lookup(entities);
+    let bodies = Bodies::new(entities);
+
+    for _ in 0..JOINT_ITERATIONS {
+        for joint in joints {
+            if let Some((a, b)) = ends(joint, entities, &lookup) {
+                constrain_velocity(entities, &bodies, joint, &a, &b);
+            }
+        }
+    }
+    for _ in 0..JOINT_ITERATIONS {
+        for joint in joints {
+            if let Some((a, b)) = ends(joint, entities, &lookup) {
+                constrain_position(entities, &bodies, joint, &a, &b);
+            }
+        }
+    }
+}
+
+/// removes the relative velocity of the anchors along each direction the joint holds them in.
+fn constrain_velocity(entities: &mut [Entity], bodies: &Bodies, joint: &Joint, a: &End, b: &End) {
+    let offset = EuclidianVector::from(a.point, b.point);
+    let directions = match joint.kind {
+        JointKind::Spring { .. } => return,
+        JointKind::Revolute => vec![
+            EuclidianVector::new(1.0, 0.0),
+            EuclidianVector::new(0.0, 1.0),
+        ],
+        JointKind::Distance { .. } if offset.magnitude() < JOINT_EPSILON => return,
+        JointKind::Distance { .. } => vec![offset.unit()],
+        // slack ropes don't hold the anchors at all
+        JointKind::Rope { length } if offset.magnitude() < length => return,
+        JointKind::Rope { .. } => vec![offset.unit()],
+    };
+
+    for direction in directions {
+        let separating = (b.vel(entities) - a.vel(entities)).dot(&direction);
+        // ropes can only pull the anchors together
+        if matches!(joint.kind, JointKind::Rope { .. }) && separating <= 0.0 {
+            continue;
+        }
+        let inverse = a.inverse_effective_mass(entities, bodies, &direction)
+            + b.inverse_effective_mass(entities, bodies, &direction);
+        if inverse <= 0.0 {
+            continue;
+        }
+        let impulse = direction * (separating / inverse);
+        a.push(entities, bodies, &impulse);
+        b.push(entities, bodies, &(impulse * -1.0));
+    }
+}
+
+/// moves the entities so their anchors are as far apart as the joint allows,
+/// sharing the correction between the entities by their mass.
+fn constrain_position(entities: &mut [Entity], bodies: &Bodies, joint: &Joint, a: &End, b: &End) {
+    let offset = EuclidianVector::from(a.point, b.point);
+    let length = offset.magnitude();
+    let error = match joint.kind {
+        JointKind::Spring { .. } => return,
+        JointKind::Revolute => offset,
+        JointKind::Distance { .. } if length < JOINT_EPSILON => return,
+        JointKind::Distance { length: target } => offset.unit() * (length - target),
+        JointKind::Rope { length: max } if length <= max => return,
+        JointKind::Rope { length: max } => offset.unit() * (length - max),
+    };
+
+    let (inv_mass_a, inv_mass_b) = (a.inverse_mass(bodies), b.inverse_mass(bodies));
+    if inv_mass_a + inv_mass_b <= 0.0 {
+        return;
+    }
+    let share = 1.0 / (inv_mass_a + inv_mass_b);
+    for (end, correction) in [(a, inv_mass_a * share), (b, -inv_mass_b * share)] {
+        if let Some(idx) = end.idx {
+            bodies.translate(entities, idx, error.x * correction, error.y * correction);
+        }
+    }
+}
+
+fn lookup(entities: &[Entity]) -> HashMap<Uuid, usize> {
+    entities
+        .iter()
+        .enumerate()
+        .map(|(idx, entity)| (entity.uuid, idx))
+        .collect()
+}
+
+/// returns where both ends of the joint are, if both are still in the world,
+/// and at least one of them can be moved.
+fn ends(joint: &Joint, entities: &[Entity], lookup: &HashMap<Uuid, usize>) -> Option<(End, End)> {
+    let (a, b) = (
+        End::new(&joint.a, entities, lookup)?,
+        End::new(&joint.b, entities, lookup)?,
+    );
+    let resting = |end: &End| end.idx.is_none_or(|idx| at_rest(&entities[idx]));
+    if resting(&a) && resting(&b) {
+        return None;
+    }
+    Some((a, b))
+}
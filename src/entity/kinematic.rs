@@ -0,0 +1,126 @@
+use super::vector::EuclidianVector;
+use super::{Entity, WorldConfig};
+
+// -------------------------------------------------------------------------- //
+// ------------------- SCRIPTED MOTION ALONG WAYPOINTS ---------------------- //
+// -------------------------------------------------------------------------- //
+
+/// What a path does once it reaches its last waypoint.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathMode {
+    /// stops at the last waypoint.
+    #[default]
+    Once,
+    /// turns around and heads back through the waypoints to the first, and so on (e.g. an elevator).
+    PingPong,
+    /// carries on from the last waypoint back to the first, and so on (e.g. a circuit).
+    Loop,
+}
+
+/// How speed changes along each leg of a path (between neighbouring waypoints).
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Easing {
+    /// constant speed from one waypoint to the next, turning sharply at each waypoint.
+    #[default]
+    Linear,
+    /// speeds up away from each waypoint and slows down into the next, stopping at every waypoint
+    /// (See "smoothstep"). takes the same time per leg as `Linear`, peaking at 1.5x the speed.
+    Smooth,
+}
+
+/// The route a kinematic entity follows, travelling between waypoints at some speed.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct Path {
+    pub waypoints: Vec<(f32, f32)>,
+    pub speed: f32,
+    pub mode: PathMode,
+    pub easing: Easing,
+
+    /// how many seconds the path has been followed for.
+    pub elapsed: f32,
+}
+
+impl Path {
+    /// creates a path through the waypoints, travelled once at constant speed.
+    pub fn new(waypoints: Vec<(f32, f32)>, speed: f32) -> Path {
+        Path {
+            waypoints,
+            speed,
+            mode: PathMode::default(),
+            easing: Easing::default(),
+            elapsed: 0.0,
+        }
+    }
+
+    /// returns the (start, end) of every leg of the path, in the order they are travelled.
+    fn legs(&self) -> Vec<((f32, f32), (f32, f32))> {
+        let forwards = self.waypoints.windows(2).map(|pair| (pair[0], pair[1]));
+        match self.mode {
+            PathMode::Once => forwards.collect(),
+            PathMode::PingPong => {
+                let backwards = forwards.clone().rev().map(|(from, to)| (to, from));
+                forwards.chain(backwards).collect()
+            }
+            PathMode::Loop => {
+                let (first, last) = (self.waypoints[0], self.waypoints[self.waypoints.len() - 1]);
+                forwards.chain([(last, first)]).collect()
+            }
+        }
+    }
+
+    /// returns where along the path its follower is after `elapsed` seconds,
+    /// or `None` if the path has no waypoints.
+    pub fn position(&self) -> Option<(f32, f32)> {
+        let first = *self.waypoints.first()?;
+        let legs = self.legs();
+        let durations: Vec<f32> = legs
+            .iter()
+            .map(|(from, to)| EuclidianVector::from(*from, *to).magnitude() / self.speed)
+            .collect();
+        let total: f32 = durations.iter().sum();
+        if !total.is_finite() || total <= 0.0 {
+            return Some(first);
+        }
+
+        let mut time = match self.mode {
+            PathMode::Once => self.elapsed.min(total),
+            PathMode::PingPong | PathMode::Loop => self.elapsed.rem_euclid(total),
+        };
+        for ((from, to), duration) in legs.iter().zip(durations) {
+            if time > duration {
+                time -= duration;
+                continue;
+            }
+            let t = if duration > 0.0 { time / duration } else { 1.0 };
+            let t = match self.easing {
+                Easing::Linear => t,
+                Easing::Smooth => t * t * (3.0 - 2.0 * t),
+            };
+            return Some((from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t));
+        }
+        legs.last().map(|(_, to)| *to)
+    }
+}
+
+/// Moves a kinematic entity to where its path has reached by the end of this step,
+/// setting its velocity to match so whatever it pushes (or carries) moves along with it.
+/// Forces applied to the entity are discarded, and entities without a path stay put.
+pub(crate) fn follow(entity: &mut Entity, config: &WorldConfig) {
+    let dt = config.time_step;
+    entity.input_force = EuclidianVector::new(0.0, 0.0);
+    entity.torque = 0.0;
+    entity.acc = EuclidianVector::new(0.0, 0.0);
+    entity.angular_vel = 0.0;
+
+    let target = entity.path.as_mut().and_then(|path| {
+        path.elapsed += dt;
+        path.position()
+    });
+    match target {
+        Some(target) => {
+            entity.vel = EuclidianVector::from(entity.pos, target) * (1.0 / dt);
+            entity.pos = target;
+        }
+        None => entity.vel = EuclidianVector::new(0.0, 0.0),
+    }
+}
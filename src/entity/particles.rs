@@ -0,0 +1,260 @@
+use std::f32::consts::PI;
+
+use super::broadphase::SpatialHash;
+use super::collision::Shape;
+use super::determinism::SeededRng;
+use super::raycast::Ray;
+use super::vector::EuclidianVector;
+use super::{Entity, EntityState, EntityType, WorldConfig};
+
+// -------------------------------------------------------------------------- //
+// ---------------- CHEAP PARTICLE EFFECTS (SPARKS, RAIN, SMOKE) ------------ //
+// -------------------------------------------------------------------------- //
+
+/// how far outside a surface particles are left after bouncing off it,
+/// so they aren't found inside it on their next step.
+const PARTICLE_SKIN: f32 = 1e-3;
+
+/// a colour, given as its (red, green, blue) intensities.
+pub type Rgb = (u8, u8, u8);
+
+/// Emits particles, either steadily over time (e.g. exhaust or rain), or in bursts
+/// (e.g. explosions, see `ParticleSystem::burst`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Emitter {
+    pub pos: (f32, f32),
+
+    /// half the width and height of the area particles are emitted across,
+    /// e.g. a wide, flat area along the top of the world for rain.
+    pub area: (f32, f32),
+
+    /// how many particles are emitted per second (zero for an emitter that only bursts).
+    pub rate: f32,
+
+    /// the direction particles are emitted in, in radians clockwise (on screen) from the x-axis,
+    /// and how far either side of it they may stray (PI to emit in every direction).
+    pub direction: f32,
+    pub spread: f32,
+
+    /// the (lower, upper) limits of the speed particles are emitted at.
+    pub speed: (f32, f32),
+
+    /// the (lower, upper) limits of how many seconds each particle lasts.
+    pub lifetime: (f32, f32),
+
+    /// the colours particles fade through from being emitted until they expire,
+    /// spaced evenly over their lifetime.
+    pub ramp: Vec<Rgb>,
+
+    /// how strongly the world's gravity pulls on the particles (zero for smoke to hang in the air).
+    pub gravity_scale: f32,
+
+    /// whether particles bounce off static entities, rather than passing through them,
+    /// and the fraction of their speed into the entity that they keep when they do.
+    pub collide: bool,
+    pub bounce: f32,
+}
+
+impl Default for Emitter {
+    fn default() -> Self {
+        Emitter {
+            pos: (0.0, 0.0),
+            area: (0.0, 0.0),
+            rate: 0.0,
+            direction: 0.0,
+            spread: PI,
+            speed: (1.0, 1.0),
+            lifetime: (1.0, 1.0),
+            ramp: vec![(255, 255, 255)],
+            gravity_scale: 1.0,
+            collide: false,
+            bounce: 0.5,
+        }
+    }
+}
+
+impl Emitter {
+    /// returns the colour of the ramp some fraction of the way through a particle's lifetime,
+    /// blending between neighbouring colours.
+    pub fn colour_at(&self, fraction: f32) -> Rgb {
+        ramp_colour(&self.ramp, fraction)
+    }
+}
+
+/// returns the colour some fraction of the way through a ramp, blending between neighbouring
+/// colours (or white, for an empty ramp).
+fn ramp_colour(ramp: &[Rgb], fraction: f32) -> Rgb {
+    let Some(last) = ramp.len().checked_sub(1) else {
+        return (255, 255, 255);
+    };
+    let position = fraction.clamp(0.0, 1.0) * last as f32;
+    let idx = (position.floor() as usize).min(last);
+    let (from, to) = (ramp[idx], ramp[(idx + 1).min(last)]);
+    let t = position - idx as f32;
+    let blend = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    (
+        blend(from.0, to.0),
+        blend(from.1, to.1),
+        blend(from.2, to.2),
+    )
+}
+
+/// A single particle, moved by its velocity and gravity alone.
+/// Particles never push (or are pushed by) entities.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Particle {
+    pub pos: (f32, f32),
+    pub vel: EuclidianVector,
+
+    /// how many seconds the particle has existed for, and will exist for in total.
+    pub age: f32,
+    pub lifetime: f32,
+    pub colour: Rgb,
+
+    /// the settings of the emitter the particle came from, kept with the particle
+    /// so it carries on the same way once the emitter is gone (see `Emitter`)
+    ramp: Vec<Rgb>,
+    gravity_scale: f32,
+    collide: bool,
+    bounce: f32,
+}
+
+/// Emitters, and every particle they've emitted that has yet to expire.
+/// Updated by the world each step (see `PhysicsWorld::particles`).
+#[derive(Clone, Debug)]
+pub struct ParticleSystem {
+    pub emitters: Vec<Emitter>,
+    particles: Vec<Particle>,
+
+    /// fractions of a particle each emitter is yet to emit, carried over between updates
+    pub(crate) owed: Vec<f32>,
+    pub(crate) rng: SeededRng,
+}
+
+impl Default for ParticleSystem {
+    fn default() -> Self {
+        ParticleSystem::new(0)
+    }
+}
+
+impl ParticleSystem {
+    /// creates an empty particle system, which emits particles the same way on every run
+    /// for the same seed.
+    pub fn new(seed: u64) -> ParticleSystem {
+        ParticleSystem {
+            emitters: Vec::new(),
+            particles: Vec::new(),
+            owed: Vec::new(),
+            rng: SeededRng::new(seed),
+        }
+    }
+
+    /// adds an emitter, returning its index in `emitters`.
+    pub fn add_emitter(&mut self, emitter: Emitter) -> usize {
+        self.emitters.push(emitter);
+        self.emitters.len() - 1
+    }
+
+    /// returns every particle that has yet to expire, oldest first.
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    /// emits a number of particles from an emitter all at once.
+    /// does nothing if there is no emitter at that index.
+    pub fn burst(&mut self, emitter: usize, count: usize) {
+        for _ in 0..count {
+            self.emit(emitter);
+        }
+    }
+
+    /// removes every particle, leaving the emitters in place.
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    fn emit(&mut self, idx: usize) {
+        let Some(emitter) = self.emitters.get(idx) else {
+            return;
+        };
+        let rng = &mut self.rng;
+        let angle = emitter.direction + rng.range(-emitter.spread, emitter.spread);
+        let speed = rng.range(emitter.speed.0, emitter.speed.1);
+        let lifetime = rng.range(emitter.lifetime.0, emitter.lifetime.1);
+        let pos = (
+            emitter.pos.0 + rng.range(-emitter.area.0, emitter.area.0),
+            emitter.pos.1 + rng.range(-emitter.area.1, emitter.area.1),
+        );
+        self.particles.push(Particle {
+            pos,
+            vel: EuclidianVector::new(angle.cos(), angle.sin()) * speed,
+            age: 0.0,
+            lifetime,
+            colour: emitter.colour_at(0.0),
+            ramp: emitter.ramp.clone(),
+            gravity_scale: emitter.gravity_scale,
+            collide: emitter.collide,
+            bounce: emitter.bounce,
+        });
+    }
+
+    /// emits what each emitter is due this step, then ages and moves every particle,
+    /// bouncing those that collide off any static entities in their way
+    /// (other than any they start the step inside of, which they pass out of).
+    /// particles are removed once they expire or leave the world's bounds.
+    pub fn update(&mut self, config: &WorldConfig, entities: &[Entity]) {
+        let dt = config.time_step;
+        self.owed.resize(self.emitters.len(), 0.0);
+        for idx in 0..self.emitters.len() {
+            self.owed[idx] += self.emitters[idx].rate * dt;
+            let due = self.owed[idx].floor();
+            self.owed[idx] -= due;
+            self.burst(idx, due as usize);
+        }
+
+        // only static entities stop particles, and only those near a particle could be in its way
+        let walls = |entity: &Entity| {
+            entity.id == EntityType::Static && matches!(entity.state, EntityState::Alive)
+        };
+        let grid = self
+            .particles
+            .iter()
+            .any(|particle| particle.collide)
+            .then(|| SpatialHash::new_filtered(entities, walls));
+        let bounds = &config.bounds;
+        self.particles.retain_mut(|particle| {
+            particle.age += dt;
+            if particle.age >= particle.lifetime {
+                return false;
+            }
+            particle.vel += config.gravity.clone() * (particle.gravity_scale * dt);
+            let to = (
+                particle.pos.0 + particle.vel.x * dt,
+                particle.pos.1 + particle.vel.y * dt,
+            );
+
+            // particles emitted inside a wall (e.g. sparks from a newly placed wall) pass out
+            // of it, rather than bouncing about inside it until they expire
+            let outside = |wall: &Entity| !Shape::of(wall).contains(&particle.pos);
+            let hit = grid.as_ref().filter(|_| particle.collide).and_then(|grid| {
+                Ray::segment(particle.pos, to).cast_nearby(entities, grid, outside)
+            });
+            match hit {
+                // stop just outside the surface, and reflect the velocity into it
+                Some(hit) => {
+                    let into = particle.vel.dot(&hit.normal).min(0.0);
+                    particle.vel -= hit.normal.clone() * ((1.0 + particle.bounce) * into);
+                    particle.pos = (
+                        hit.point.0 + hit.normal.x * PARTICLE_SKIN,
+                        hit.point.1 + hit.normal.y * PARTICLE_SKIN,
+                    );
+                }
+                None => particle.pos = to,
+            }
+            particle.colour = ramp_colour(&particle.ramp, particle.age / particle.lifetime);
+
+            let (x, y) = particle.pos;
+            x >= bounds.left && x <= bounds.right && y >= bounds.top && y <= bounds.bottom
+        });
+    }
+}
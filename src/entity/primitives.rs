@@ -1,5 +1,11 @@
 use core::f32;
 
+use super::contact::Manifold;
+use super::vector::EuclidianVector;
+
+/// overlaps smaller than this are treated as "just touching" rather than colliding
+const DEADZONE: f32 = 0.01;
+
 // -------------------------------------------------------------------------- //
 // --------------- INTERSECTION TESTS FOR DIFFERENT SHAPES ------------------ //
 // -------------------------------------------------------------------------- //
@@ -16,14 +22,20 @@ impl Circle<'_> {
     }
 
     /// returns true if the two circles described by the input parameters are intersecting.
-    // in other words: is the separation distance between their centroids,
-    // along both x and y axes, less than the sum of their radii?
+    // in other words: is the separation distance between their centroids
+    // less than the sum of their radii?
     pub fn intersects(&self, other: &Circle) -> bool {
-        let dx = (self.centroid.0 - other.centroid.0).abs();
-        let dy = (self.centroid.1 - other.centroid.1).abs();
+        let dx = self.centroid.0 - other.centroid.0;
+        let dy = self.centroid.1 - other.centroid.1;
         let r = self.radius + other.radius;
 
-        dx <= r && dy <= r
+        dx * dx + dy * dy <= r * r
+    }
+
+    /// returns true if the point lies inside (or on the boundary of) the circle.
+    pub fn contains(&self, point: &(f32, f32)) -> bool {
+        let offset = EuclidianVector::from(*self.centroid, *point);
+        offset.dot(&offset) <= self.radius * self.radius
     }
 
     /// returns length of the overlap between two circles.
@@ -38,6 +50,119 @@ impl Circle<'_> {
         let centroid_separation_distance = (dx_pow2 + dy_pow2).sqrt();
         self.radius + other.radius - centroid_separation_distance
     }
+
+    /// returns the manifold describing how this circle is touching the other circle.
+    pub fn contact(&self, other: &Circle) -> Option<Manifold> {
+        let separation = EuclidianVector::from(*self.centroid, *other.centroid);
+        let distance = separation.magnitude();
+        let depth = self.radius + other.radius - distance;
+
+        if depth <= DEADZONE {
+            return None;
+        }
+
+        // concentric circles have no preferred direction, so push them apart vertically
+        let normal = if distance > 0.0 {
+            separation.unit()
+        } else {
+            EuclidianVector::new(0.0, 1.0)
+        };
+
+        // the contact point lies midway through the overlapping region
+        let reach = self.radius - 0.5 * depth;
+        let point = (
+            self.centroid.0 + normal.x * reach,
+            self.centroid.1 + normal.y * reach,
+        );
+        Some(Manifold {
+            normal,
+            depth,
+            points: vec![point],
+        })
+    }
+
+    /// returns the distance along a ray (with unit-length direction) until it first meets
+    /// the circle, and the circle's surface normal at that point.
+    /// a ray starting inside the circle hits immediately, facing back along the ray.
+    /// a ray starting on its surface only hits it when heading inwards.
+    pub fn raycast(
+        &self,
+        origin: &(f32, f32),
+        direction: &EuclidianVector,
+    ) -> Option<(f32, EuclidianVector)> {
+        // solve |origin + t * direction - centroid| = radius for the smallest t
+        let offset = EuclidianVector::from(*self.centroid, *origin);
+        let b = offset.dot(direction);
+        let c = offset.dot(&offset) - self.radius * self.radius;
+
+        if c < 0.0 {
+            return Some((0.0, direction.clone() * -1.0));
+        }
+        // starting outside and pointing away
+        if b > 0.0 {
+            return None;
+        }
+        let discriminant = b * b - c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let distance = -b - discriminant.sqrt();
+        let point = (
+            origin.0 + direction.x * distance,
+            origin.1 + direction.y * distance,
+        );
+        Some((
+            distance,
+            EuclidianVector::from(*self.centroid, point).unit(),
+        ))
+    }
+
+    /// returns the manifold describing how this circle is touching the square.
+    pub fn contact_square(&self, square: &Square) -> Option<Manifold> {
+        self.contact_aabb(&Aabb::from_square(square))
+    }
+
+    /// returns the manifold describing how this circle is touching the box.
+    pub fn contact_aabb(&self, aabb: &Aabb) -> Option<Manifold> {
+        let (cx, cy) = *self.centroid;
+        let (bx, by) = *aabb.centroid;
+        let (w, h) = aabb.half_extents;
+
+        // the point on (or in) the box that is closest to the circle's centroid
+        let closest = (cx.clamp(bx - w, bx + w), cy.clamp(by - h, by + h));
+
+        if closest != (cx, cy) {
+            // the centroid is outside the box,
+            // so the contact is with the closest edge (or corner).
+            let separation = EuclidianVector::from((cx, cy), closest);
+            let depth = self.radius - separation.magnitude();
+            if depth <= DEADZONE {
+                return None;
+            }
+            return Some(Manifold {
+                normal: separation.unit(),
+                depth,
+                points: vec![closest],
+            });
+        }
+
+        // the centroid is inside the box,
+        // so the circle needs pushing out through the nearest side.
+        let (dx, dy) = (cx - bx, cy - by);
+        if w - dx.abs() < h - dy.abs() {
+            Some(Manifold {
+                normal: EuclidianVector::new(-dx.signum(), 0.0),
+                depth: self.radius + w - dx.abs(),
+                points: vec![(bx + dx.signum() * w, cy)],
+            })
+        } else {
+            Some(Manifold {
+                normal: EuclidianVector::new(0.0, -dy.signum()),
+                depth: self.radius + h - dy.abs(),
+                points: vec![(cx, by + dy.signum() * h)],
+            })
+        }
+    }
 }
 
 /// represents a spatial coordinate
@@ -62,18 +187,416 @@ impl Square<'_> {
     /// returns the (width, height) of the rectangle that would be formed
     /// from the overlapping area between this Square and some other Square.
     pub fn overlap(&self, other: &Square) -> Option<(f32, f32)> {
-        let deadzone: f32 = 0.01;
+        Aabb::from_square(self).overlap(&Aabb::from_square(other))
+    }
 
+    /// returns the manifold describing how this square is touching the other square.
+    /// the normal is orthogonal to the side with the least overlap,
+    /// as that is the shortest way to separate the two squares.
+    pub fn contact(&self, other: &Square) -> Option<Manifold> {
+        Aabb::from_square(self).contact(&Aabb::from_square(other))
+    }
+}
+
+/// An axis-aligned rectangle, which can be wider than it is tall (or vice versa).
+pub struct Aabb<'a> {
+    /// A point that is at the geometric center.
+    centroid: &'a (f32, f32),
+
+    /// The shortest distances from the centroid to the vertical and horizontal sides.
+    half_extents: (f32, f32),
+}
+
+impl Aabb<'_> {
+    /// creates a new box using the centroid owned by caller.
+    pub fn new(centroid: &(f32, f32), half_width: f32, half_height: f32) -> Aabb<'_> {
+        Aabb {
+            centroid,
+            half_extents: (half_width, half_height),
+        }
+    }
+
+    /// creates the box with the same footprint as a square.
+    pub fn from_square<'a>(square: &Square<'a>) -> Aabb<'a> {
+        Aabb::new(square.centroid, *square.apothem, *square.apothem)
+    }
+
+    /// returns true if the point lies inside (or on the boundary of) the box.
+    pub fn contains(&self, point: &(f32, f32)) -> bool {
+        let (w, h) = self.half_extents;
+        (point.0 - self.centroid.0).abs() <= w && (point.1 - self.centroid.1).abs() <= h
+    }
+
+    /// returns the distance along a ray (with unit-length direction) until it first meets
+    /// the box, and the box's surface normal at that point.
+    /// a ray starting inside the box hits immediately, facing back along the ray.
+    /// a ray starting on its surface only hits it when heading inwards.
+    pub fn raycast(
+        &self,
+        origin: &(f32, f32),
+        direction: &EuclidianVector,
+    ) -> Option<(f32, EuclidianVector)> {
+        let (w, h) = self.half_extents;
+        let slabs = [
+            (
+                origin.0,
+                direction.x,
+                self.centroid.0,
+                w,
+                EuclidianVector::new(1.0, 0.0),
+            ),
+            (
+                origin.1,
+                direction.y,
+                self.centroid.1,
+                h,
+                EuclidianVector::new(0.0, 1.0),
+            ),
+        ];
+
+        // the ray is inside the box wherever it is between both pairs of sides
+        // (See "slab test", Ericson Ch5)
+        let (mut enter, mut exit) = (f32::NEG_INFINITY, f32::INFINITY);
+        let mut normal = direction.clone() * -1.0;
+        for (start, step, center, extent, axis) in slabs {
+            if step == 0.0 {
+                // running parallel to the sides, so it must already be between them
+                if (start - center).abs() > extent {
+                    return None;
+                }
+                continue;
+            }
+            let near = (center - step.signum() * extent - start) / step;
+            let far = (center + step.signum() * extent - start) / step;
+            if near > enter {
+                enter = near;
+                normal = axis * -step.signum();
+            }
+            exit = exit.min(far);
+        }
+
+        if enter > exit || exit <= 0.0 {
+            None
+        } else if enter < 0.0 {
+            Some((0.0, direction.clone() * -1.0))
+        } else {
+            Some((enter, normal))
+        }
+    }
+
+    /// returns the (width, height) of the rectangle that would be formed
+    /// from the overlapping area between this box and some other box.
+    pub fn overlap(&self, other: &Aabb) -> Option<(f32, f32)> {
         let dx = (self.centroid.0 - other.centroid.0).abs();
         let dy = (self.centroid.1 - other.centroid.1).abs();
 
-        let x_overlap = self.apothem + other.apothem - dx;
-        let y_overlap = self.apothem + other.apothem - dy;
+        let x_overlap = self.half_extents.0 + other.half_extents.0 - dx;
+        let y_overlap = self.half_extents.1 + other.half_extents.1 - dy;
 
-        if x_overlap <= deadzone || y_overlap <= deadzone {
+        if x_overlap <= DEADZONE || y_overlap <= DEADZONE {
             None
         } else {
             Some((x_overlap, y_overlap))
         }
     }
+
+    /// returns the manifold describing how this box is touching the other box.
+    /// the normal is orthogonal to the side with the least overlap,
+    /// as that is the shortest way to separate the two boxes.
+    pub fn contact(&self, other: &Aabb) -> Option<Manifold> {
+        let (x_overlap, y_overlap) = self.overlap(other)?;
+        let (w, h) = self.half_extents;
+        let (ow, oh) = other.half_extents;
+
+        // the overlapping region between the two boxes
+        let left = (self.centroid.0 - w).max(other.centroid.0 - ow);
+        let right = (self.centroid.0 + w).min(other.centroid.0 + ow);
+        let top = (self.centroid.1 - h).max(other.centroid.1 - oh);
+        let bottom = (self.centroid.1 + h).min(other.centroid.1 + oh);
+        let (mid_x, mid_y) = (0.5 * (left + right), 0.5 * (top + bottom));
+
+        let direction = |a: f32, b: f32| if b >= a { 1.0 } else { -1.0 };
+        let (dir_x, dir_y) = (
+            direction(self.centroid.0, other.centroid.0),
+            direction(self.centroid.1, other.centroid.1),
+        );
+
+        // the contact points span the overlapping region along the touching sides,
+        // boxes overlapping equally along both axes met corner-first, so are pushed apart along both.
+        if y_overlap < x_overlap {
+            Some(Manifold {
+                normal: EuclidianVector::new(0.0, dir_y),
+                depth: y_overlap,
+                points: vec![(left, mid_y), (right, mid_y)],
+            })
+        } else if x_overlap < y_overlap {
+            Some(Manifold {
+                normal: EuclidianVector::new(dir_x, 0.0),
+                depth: x_overlap,
+                points: vec![(mid_x, top), (mid_x, bottom)],
+            })
+        } else {
+            let separation = EuclidianVector::new(dir_x * x_overlap, dir_y * y_overlap);
+            Some(Manifold {
+                normal: separation.unit(),
+                depth: separation.magnitude(),
+                points: vec![(mid_x, mid_y)],
+            })
+        }
+    }
+}
+
+/// A convex polygon, with its vertices positioned in world-space.
+pub struct Polygon {
+    /// A point that is at the geometric center.
+    centroid: (f32, f32),
+
+    /// The corners of the polygon, in order around its perimeter.
+    vertices: Vec<(f32, f32)>,
+}
+
+impl Polygon {
+    /// creates a new polygon from vertices given relative to its centroid,
+    /// rotated clockwise (on screen) about the centroid by some angle in radians.
+    pub fn new(centroid: &(f32, f32), vertices: &[(f32, f32)], orientation: f32) -> Polygon {
+        let (sin, cos) = orientation.sin_cos();
+        let vertices = vertices
+            .iter()
+            .map(|(x, y)| {
+                (
+                    centroid.0 + x * cos - y * sin,
+                    centroid.1 + x * sin + y * cos,
+                )
+            })
+            .collect();
+        Polygon {
+            centroid: *centroid,
+            vertices,
+        }
+    }
+
+    /// creates the polygon with the same footprint as a square.
+    pub fn from_square(square: &Square) -> Polygon {
+        Polygon::from_aabb(&Aabb::from_square(square))
+    }
+
+    /// creates the polygon with the same footprint as an axis-aligned box.
+    pub fn from_aabb(aabb: &Aabb) -> Polygon {
+        let (w, h) = aabb.half_extents;
+        Polygon::new(aabb.centroid, &[(-w, -h), (w, -h), (w, h), (-w, h)], 0.0)
+    }
+
+    /// returns the corners of the polygon.
+    pub fn vertices(&self) -> &[(f32, f32)] {
+        &self.vertices
+    }
+
+    /// returns true if the point lies inside (or on the boundary of) the polygon.
+    /// polygons with fewer than three vertices have no inside, so contain nothing.
+    pub fn contains(&self, point: &(f32, f32)) -> bool {
+        if self.vertices.len() < 3 {
+            return false;
+        }
+        let mut winding: f32 = 0.0;
+        for (a, b) in self.edges() {
+            let edge = EuclidianVector::from(a, b);
+            let to_point = EuclidianVector::from(a, *point);
+            let side = edge.cross(&to_point);
+
+            // a point on the inside is on the same side of every edge
+            if side.abs() <= f32::EPSILON {
+                continue;
+            }
+            if winding != 0.0 && side.signum() != winding {
+                return false;
+            }
+            winding = side.signum();
+        }
+        true
+    }
+
+    /// returns the manifold describing how this polygon is touching the other polygon,
+    /// found by testing for a separating axis along the normal of every edge
+    /// (See "Separating Axis Theorem", Ericson Ch5).
+    pub fn contact(&self, other: &Polygon) -> Option<Manifold> {
+        let axes: Vec<EuclidianVector> = self.axes().chain(other.axes()).collect();
+        let (normal, depth) =
+            least_overlap(&axes, |axis| (self.project(axis), other.project(axis)))?;
+
+        // the contact points are wherever a corner of one polygon is inside the other
+        let mut points: Vec<(f32, f32)> = other
+            .vertices
+            .iter()
+            .filter(|v| self.contains(v))
+            .chain(self.vertices.iter().filter(|v| other.contains(v)))
+            .copied()
+            .collect();
+
+        // edges crossing without any corners inside, so take the deepest corner instead
+        if points.is_empty() {
+            points.push(other.support(&(normal.clone() * -1.0)));
+        }
+        Some(Manifold {
+            normal,
+            depth,
+            points,
+        })
+    }
+
+    /// returns the manifold describing how this polygon is touching the circle.
+    pub fn contact_circle(&self, circle: &Circle) -> Option<Manifold> {
+        let center = *circle.centroid;
+        let radius = *circle.radius;
+
+        // besides the edge normals, the circle could be touching the closest corner
+        let closest_vertex = self.vertices.iter().copied().min_by(|a, b| {
+            let da = EuclidianVector::from(*a, center).magnitude();
+            let db = EuclidianVector::from(*b, center).magnitude();
+            da.total_cmp(&db)
+        })?;
+        let corner_axis = EuclidianVector::from(closest_vertex, center).unit();
+
+        let mut axes: Vec<EuclidianVector> = self.axes().collect();
+        if corner_axis.magnitude() > 0.0 {
+            axes.push(corner_axis);
+        }
+
+        let (normal, depth) = least_overlap(&axes, |axis| {
+            let c = axis.dot(&EuclidianVector::new(center.0, center.1));
+            (self.project(axis), (c - radius, c + radius))
+        })?;
+
+        // the deepest point on the circle
+        let point = (center.0 - normal.x * radius, center.1 - normal.y * radius);
+        Some(Manifold {
+            normal,
+            depth,
+            points: vec![point],
+        })
+    }
+
+    /// returns the distance along a ray (with unit-length direction) until it first meets
+    /// the polygon, and the polygon's surface normal at that point.
+    /// a ray starting inside the polygon hits immediately, facing back along the ray.
+    /// a ray starting on its surface only hits it when heading inwards.
+    /// polygons with fewer than three vertices have no inside, so are never hit.
+    pub fn raycast(
+        &self,
+        origin: &(f32, f32),
+        direction: &EuclidianVector,
+    ) -> Option<(f32, EuclidianVector)> {
+        if self.vertices.len() < 3 {
+            return None;
+        }
+        // clip the ray against the inside of every edge (See "Cyrus-Beck", Ericson Ch5)
+        let (mut enter, mut exit) = (f32::NEG_INFINITY, f32::INFINITY);
+        let mut normal = direction.clone() * -1.0;
+        for ((vertex, _), axis) in self.edges().zip(self.axes()) {
+            // the axes point outwards or inwards depending on the winding order
+            let outwards = if axis.dot(&EuclidianVector::from(self.centroid, vertex)) < 0.0 {
+                axis * -1.0
+            } else {
+                axis
+            };
+            let facing = outwards.dot(direction);
+            let clearance = outwards.dot(&EuclidianVector::from(*origin, vertex));
+
+            if facing == 0.0 {
+                // running parallel to the edge, so it must already be on the inside of it
+                if clearance < 0.0 {
+                    return None;
+                }
+            } else if facing < 0.0 {
+                let distance = clearance / facing;
+                if distance > enter {
+                    enter = distance;
+                    normal = outwards;
+                }
+            } else {
+                exit = exit.min(clearance / facing);
+            }
+        }
+
+        if enter > exit || exit <= 0.0 {
+            None
+        } else if enter < 0.0 {
+            Some((0.0, direction.clone() * -1.0))
+        } else {
+            Some((enter, normal))
+        }
+    }
+
+    /// returns each edge of the polygon as a pair of vertices.
+    fn edges(&self) -> impl Iterator<Item = ((f32, f32), (f32, f32))> + '_ {
+        let count = self.vertices.len();
+        (0..count).map(move |i| (self.vertices[i], self.vertices[(i + 1) % count]))
+    }
+
+    /// returns the unit-normal of each edge of the polygon.
+    fn axes(&self) -> impl Iterator<Item = EuclidianVector> + '_ {
+        self.edges().map(|(a, b)| {
+            let edge = EuclidianVector::from(a, b);
+            EuclidianVector::new(edge.y, -edge.x).unit()
+        })
+    }
+
+    /// returns the (min, max) interval covered by the polygon when projected onto the axis.
+    fn project(&self, axis: &EuclidianVector) -> (f32, f32) {
+        self.vertices
+            .iter()
+            .map(|(x, y)| axis.dot(&EuclidianVector::new(*x, *y)))
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), p| {
+                (min.min(p), max.max(p))
+            })
+    }
+
+    /// returns the vertex furthest along the given direction.
+    fn support(&self, direction: &EuclidianVector) -> (f32, f32) {
+        self.vertices
+            .iter()
+            .copied()
+            .max_by(|a, b| {
+                let pa = direction.dot(&EuclidianVector::new(a.0, a.1));
+                let pb = direction.dot(&EuclidianVector::new(b.0, b.1));
+                pa.total_cmp(&pb)
+            })
+            .unwrap_or(self.centroid)
+    }
+}
+
+/// returns the axis along which two shapes overlap the least, directed from the first shape
+/// towards the second, along with the length of that overlap.
+/// returns None if there is a separating axis (i.e. the shapes are not overlapping).
+/// the intervals covered by each shape along an axis are given by the `project` closure.
+fn least_overlap<F>(axes: &[EuclidianVector], project: F) -> Option<(EuclidianVector, f32)>
+where
+    F: Fn(&EuclidianVector) -> ((f32, f32), (f32, f32)),
+{
+    let mut least: Option<(EuclidianVector, f32)> = None;
+
+    for axis in axes {
+        // repeated vertices leave edges without a direction, which can't separate anything
+        if axis.magnitude() <= 0.0 {
+            continue;
+        }
+        let ((min_a, max_a), (min_b, max_b)) = project(axis);
+
+        // the distance either shape needs moving along the axis to separate them
+        let forwards = max_a - min_b;
+        let backwards = max_b - min_a;
+        let overlap = forwards.min(backwards);
+
+        if overlap <= DEADZONE {
+            return None;
+        }
+        if least.as_ref().is_none_or(|(_, depth)| overlap < *depth) {
+            let normal = if forwards <= backwards {
+                axis.clone()
+            } else {
+                axis.clone() * -1.0
+            };
+            least = Some((normal, overlap));
+        }
+    }
+    least
 }
@@ -0,0 +1,126 @@
+use uuid::Uuid;
+
+use super::broadphase::SpatialHash;
+use super::collision::Shape;
+use super::vector::EuclidianVector;
+use super::Entity;
+
+// -------------------------------------------------------------------------- //
+// ---------------------- LINE QUERIES AGAINST ENTITIES --------------------- //
+// -------------------------------------------------------------------------- //
+
+/// A line travelling from an origin in some direction, for a limited length.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ray {
+    pub origin: (f32, f32),
+
+    /// unit-vector in the direction of travel.
+    pub direction: EuclidianVector,
+
+    /// how far the ray travels before giving up, may be infinite.
+    pub length: f32,
+}
+
+/// Describes where a ray first meets an entity.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RayHit {
+    /// the entity that was hit
+    pub uuid: Uuid,
+
+    /// the point on the entity's hitbox where the ray meets it.
+    pub point: (f32, f32),
+
+    /// unit-vector pointing out of the hitbox's surface at the hit point,
+    /// or back along the ray if the ray started inside the hitbox.
+    pub normal: EuclidianVector,
+
+    /// how far along the ray the hit point is.
+    pub distance: f32,
+}
+
+impl Ray {
+    /// creates a ray travelling in the given direction (which needn't be unit-length).
+    pub fn new(origin: (f32, f32), direction: EuclidianVector, length: f32) -> Ray {
+        Ray {
+            origin,
+            direction: direction.unit(),
+            length,
+        }
+    }
+
+    /// creates the ray travelling from one point to another, and no further.
+    /// a segment from a point to itself only hits entities containing that point.
+    pub fn segment(from: (f32, f32), to: (f32, f32)) -> Ray {
+        let line = EuclidianVector::from(from, to);
+        Ray::new(from, line.clone(), line.magnitude())
+    }
+
+    /// returns the point the ray reaches after travelling some distance.
+    pub fn at(&self, distance: f32) -> (f32, f32) {
+        (
+            self.origin.0 + self.direction.x * distance,
+            self.origin.1 + self.direction.y * distance,
+        )
+    }
+
+    /// returns where the ray first hits any of the entities, if it hits any at all.
+    pub fn cast(&self, entities: &[Entity]) -> Option<RayHit> {
+        self.cast_filtered(entities, |_| true)
+    }
+
+    /// returns where the ray first hits any of the entities accepted by the filter,
+    /// e.g. to stop a line-of-sight check from hitting whoever is looking.
+    /// ties go to the entity that comes first in the slice.
+    pub fn cast_filtered<F>(&self, entities: &[Entity], filter: F) -> Option<RayHit>
+    where
+        F: Fn(&Entity) -> bool,
+    {
+        self.first_hit(entities.iter().filter(|entity| filter(entity)))
+    }
+
+    /// returns where the ray first hits any of the entities in the grid accepted by the filter,
+    /// only checking those the grid has near the ray (so the ray mustn't be infinite).
+    /// ties go to the entity that comes first in the slice.
+    pub(crate) fn cast_nearby<F>(
+        &self,
+        entities: &[Entity],
+        grid: &SpatialHash,
+        filter: F,
+    ) -> Option<RayHit>
+    where
+        F: Fn(&Entity) -> bool,
+    {
+        let end = self.at(self.length);
+        let min = (self.origin.0.min(end.0), self.origin.1.min(end.1));
+        let max = (self.origin.0.max(end.0), self.origin.1.max(end.1));
+        let nearby = grid.within(min, max).into_iter().map(|idx| &entities[idx]);
+        self.first_hit(nearby.filter(|entity| filter(entity)))
+    }
+
+    /// returns where the ray first hits any of the entities, in the order they are given.
+    fn first_hit<'a, I>(&self, entities: I) -> Option<RayHit>
+    where
+        I: Iterator<Item = &'a Entity>,
+    {
+        let mut first: Option<RayHit> = None;
+
+        for entity in entities {
+            let Some((distance, normal)) = Shape::of(entity).raycast(&self.origin, &self.direction)
+            else {
+                continue;
+            };
+            if distance > self.length {
+                continue;
+            }
+            if first.as_ref().is_none_or(|hit| distance < hit.distance) {
+                first = Some(RayHit {
+                    uuid: entity.uuid,
+                    point: self.at(distance),
+                    normal,
+                    distance,
+                });
+            }
+        }
+        first
+    }
+}
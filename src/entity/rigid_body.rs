@@ -0,0 +1,351 @@
+use core::fmt;
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use super::sleep::at_rest;
+use super::vector::EuclidianVector;
+use crate::entity::{Boundaries, BoundaryMode, Bounds, Entities, Entity, EntityType, WorldConfig};
+
+// -------------------------------------------------------------------------- //
+// ---------------------------- COMPOUND BODIES ----------------------------- //
+// -------------------------------------------------------------------------- //
+
+#[derive(Clone, Debug)]
+/// Represents a collection of entities that are treated as a single larger entity
+/// (e.g. paddles, platforms, crates), every part shares the same `body` uuid.
+/// The parts are simulated together: they share one center of mass, total mass and velocity,
+/// and collide as a unit.
+pub struct RigidBody {
+    pub uuid: Uuid,
+    pub parts: Entities,
+}
+
+impl RigidBody {
+    /// creates a body made up of parts placed at the given offsets from the origin.
+    pub fn new(id: EntityType, origin: (f32, f32), offsets: &[(f32, f32)]) -> RigidBody {
+        let uuid = Uuid::new_v4();
+        let parts = offsets
+            .iter()
+            .map(|(dx, dy)| {
+                let mut part = Entity::new(id, (origin.0 + dx, origin.1 + dy));
+                part.body = Some(uuid);
+                part
+            })
+            .collect();
+        RigidBody { uuid, parts }
+    }
+
+    /// creates a rectangular body, some number of cells wide and high,
+    /// with the origin at its top-left cell.
+    pub fn rectangle(id: EntityType, origin: (f32, f32), width: u16, height: u16) -> RigidBody {
+        let offsets: Vec<(f32, f32)> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x as f32, y as f32)))
+            .collect();
+        RigidBody::new(id, origin, &offsets)
+    }
+
+    /// returns the combined mass of all the parts.
+    pub fn mass(&self) -> f32 {
+        self.parts.iter().map(|part| part.mass).sum()
+    }
+
+    /// returns the mass-weighted average position of all the parts,
+    /// or none if the body has no parts.
+    pub fn center_of_mass(&self) -> Option<(f32, f32)> {
+        if self.parts.is_empty() {
+            return None;
+        }
+        let parts: Vec<&Entity> = self.parts.iter().collect();
+        Some(center_of_mass(&parts))
+    }
+
+    /// moves the whole body back within the bounds,
+    /// by however far its furthest-out part strays outside them.
+    pub fn clamp(&mut self, bounds: &Bounds) {
+        let (mut dx, mut dy): (f32, f32) = (0.0, 0.0);
+        for part in self.parts.iter() {
+            let (x, y) = bounds.clamp(part, part.pos);
+            if (x - part.pos.0).abs() > dx.abs() {
+                dx = x - part.pos.0;
+            }
+            if (y - part.pos.1).abs() > dy.abs() {
+                dy = y - part.pos.1;
+            }
+        }
+        for part in self.parts.iter_mut() {
+            part.pos.0 += dx;
+            part.pos.1 += dy;
+        }
+    }
+}
+
+impl fmt::Display for RigidBody {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for entity in self.parts.iter() {
+            write!(f, "{entity}")?
+        }
+        Ok(())
+    }
+}
+
+/// returns the mass-weighted average position of some (at least one) entities,
+/// or just their average position if they have no mass between them.
+fn center_of_mass(parts: &[&Entity]) -> (f32, f32) {
+    let mass: f32 = parts.iter().map(|part| part.mass).sum();
+    let weight = |part: &Entity| if mass > 0.0 { part.mass } else { 1.0 };
+    let (x, y, total) = parts.iter().fold((0.0, 0.0, 0.0), |(x, y, total), part| {
+        let w = weight(part);
+        (x + part.pos.0 * w, y + part.pos.1 * w, total + w)
+    });
+    (x / total, y / total)
+}
+
+/// Groups the indices of entities that move together.
+/// Entities that are not part of a compound body are in a group of their own.
+pub(crate) struct Bodies {
+    /// the group each entity belongs to
+    group_of: Vec<usize>,
+    /// the entities in each group, in the order they appear in the slice
+    groups: Vec<Vec<usize>>,
+    /// the inverse of the total mass of each group
+    inverse_masses: Vec<f32>,
+}
+
+impl Bodies {
+    pub(crate) fn new(entities: &[Entity]) -> Bodies {
+        let mut group_of: Vec<usize> = Vec::with_capacity(entities.len());
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        let mut compound: HashMap<Uuid, usize> = HashMap::new();
+
+        for (idx, entity) in entities.iter().enumerate() {
+            let group = match entity.body {
+                Some(body) => *compound.entry(body).or_insert_with(|| {
+                    groups.push(Vec::new());
+                    groups.len() - 1
+                }),
+                None => {
+                    groups.push(Vec::new());
+                    groups.len() - 1
+                }
+            };
+            groups[group].push(idx);
+            group_of.push(group);
+        }
+
+        let inverse_masses = groups
+            .iter()
+            .map(|group| {
+                let mut mass = 0.0;
+                for &idx in group {
+                    // an immovable part makes the whole body immovable
+                    if entities[idx].inverse_mass() <= 0.0 {
+                        return 0.0;
+                    }
+                    mass += entities[idx].mass;
+                }
+                1.0 / mass
+            })
+            .collect();
+
+        Bodies {
+            group_of,
+            groups,
+            inverse_masses,
+        }
+    }
+
+    /// returns the indices of every entity moving together with the entity at the given index.
+    pub(crate) fn members(&self, idx: usize) -> &[usize] {
+        &self.groups[self.group_of[idx]]
+    }
+
+    /// returns the reciprocal of the total mass of the body the entity at the given index is part of.
+    pub(crate) fn inverse_mass(&self, idx: usize) -> f32 {
+        self.inverse_masses[self.group_of[idx]]
+    }
+
+    /// returns the reciprocal of the moment of inertia of the entity at the given index.
+    /// compound bodies can't rotate, so they behave as if their moment of inertia is infinite.
+    pub(crate) fn inverse_inertia(&self, entities: &[Entity], idx: usize) -> f32 {
+        if self.members(idx).len() > 1 {
+            0.0
+        } else {
+            entities[idx].inverse_inertia()
+        }
+    }
+
+    /// changes the velocity of the entity at the given index, and every entity moving with it.
+    pub(crate) fn accelerate(&self, entities: &mut [Entity], idx: usize, dv: &EuclidianVector) {
+        for &member in self.members(idx) {
+            entities[member].vel += dv.clone();
+        }
+    }
+
+    /// moves the entity at the given index, and every entity moving with it.
+    pub(crate) fn translate(&self, entities: &mut [Entity], idx: usize, dx: f32, dy: f32) {
+        for &member in self.members(idx) {
+            entities[member].pos.0 += dx;
+            entities[member].pos.1 += dy;
+        }
+    }
+
+    /// returns the groups of entities that are parts of compound bodies.
+    pub(crate) fn compound(&self) -> impl Iterator<Item = &Vec<usize>> {
+        self.groups.iter().filter(|group| group.len() > 1)
+    }
+}
+
+/// performs force and motion calculations on the parts of compound bodies,
+/// each body is moved as a single entity driven by the sum of the forces applied to its parts.
+pub(crate) fn update(entities: &mut [Entity], config: &WorldConfig) {
+    let bodies = Bodies::new(entities);
+
+    for group in bodies.compound() {
+        let parts: Vec<&Entity> = group.iter().map(|&idx| &entities[idx]).collect();
+
+        // statics don't move, asleep bodies stay put, and kinematic parts follow their own paths
+        if parts
+            .iter()
+            .any(|part| at_rest(part) || part.id == EntityType::Kinematic)
+        {
+            continue;
+        }
+
+        // the body moves as a single entity would, driven by the sum of the forces on its parts
+        let mass: f32 = parts.iter().map(|part| part.mass).sum();
+        let com = center_of_mass(&parts);
+        let mut momentum = EuclidianVector::new(0.0, 0.0);
+        let mut force = EuclidianVector::new(0.0, 0.0);
+        for part in parts.iter() {
+            momentum += part.vel.clone() * part.mass;
+            force += part.input_force.clone();
+        }
+        // bodies without mass behave as if their mass is infinite (see `Entity::inverse_mass`)
+        let inverse_mass = if mass > 0.0 { 1.0 / mass } else { 0.0 };
+        let acc = force * inverse_mass;
+        let (pos, vel) = config.integrator.integrate(
+            com,
+            &(momentum * inverse_mass),
+            config.time_step,
+            |_, _| acc.clone(),
+        );
+
+        // within the world's limits (the world's edges are left to `contain_body`)
+        let limit = |value: f32, max: f32| value.clamp(-max, max);
+        let vel = EuclidianVector::new(limit(vel.x, config.max_vel), limit(vel.y, config.max_vel));
+        let acc = EuclidianVector::new(limit(acc.x, config.max_acc), limit(acc.y, config.max_acc));
+
+        // every part moves exactly as the body does
+        for &idx in group {
+            let part = &mut entities[idx];
+            part.pos.0 += pos.0 - com.0;
+            part.pos.1 += pos.1 - com.1;
+            part.vel = vel.clone();
+            part.acc = acc.clone();
+            part.input_force = EuclidianVector::new(0.0, 0.0);
+        }
+
+        contain_body(entities, group, config);
+    }
+}
+
+/// Keeps every entity the simulation moves within the world's bounds, moving the parts of compound
+/// bodies together, e.g. once collisions have pushed the bottom of a pile out through the floor.
+pub(crate) fn contain(entities: &mut [Entity], config: &WorldConfig) {
+    let bodies = Bodies::new(entities);
+    let moved = |entity: &Entity| !at_rest(entity) && entity.id != EntityType::Kinematic;
+    for (idx, entity) in entities.iter_mut().enumerate() {
+        if bodies.members(idx).len() == 1 && moved(entity) {
+            entity.contain(config);
+        }
+    }
+    for group in bodies.compound() {
+        if group.iter().all(|&idx| moved(&entities[idx])) {
+            contain_body(entities, group, config);
+        }
+    }
+}
+
+/// keeps the whole body within the world's bounds,
+/// shifting it back by however far its furthest-out part strayed.
+/// bodies wrap around the world as a whole, once their centre of mass crosses a wrapping edge
+/// (rather than each part wrapping as it crosses, tearing the body across the seam).
+fn contain_body(entities: &mut [Entity], group: &[usize], config: &WorldConfig) {
+    let parts: Vec<&Entity> = group.iter().map(|&idx| &entities[idx]).collect();
+    let mut shift = wrap_shift(center_of_mass(&parts), config);
+
+    // every other edge holds (or lets through) each part as it would any entity
+    let let_through = |mode| match mode {
+        BoundaryMode::Wrap => BoundaryMode::Despawn,
+        mode => mode,
+    };
+    let edges = &config.boundaries;
+    let unwrapped = WorldConfig {
+        boundaries: Boundaries {
+            left: let_through(edges.left),
+            top: let_through(edges.top),
+            right: let_through(edges.right),
+            bottom: let_through(edges.bottom),
+        },
+        ..config.clone()
+    };
+
+    let mut vel = entities[group[0]].vel.clone();
+    let mut floor: Option<EuclidianVector> = None;
+    for &idx in group {
+        let mut part = entities[idx].clone();
+        part.grounded = false;
+        part.contain(&unwrapped);
+        let (dx, dy) = (
+            part.pos.0 - entities[idx].pos.0,
+            part.pos.1 - entities[idx].pos.1,
+        );
+        if dx.abs() > shift.0.abs() {
+            shift.0 = dx;
+            vel.x = part.vel.x;
+        }
+        if dy.abs() > shift.1.abs() {
+            shift.1 = dy;
+            vel.y = part.vel.y;
+        }
+        // the parts share a velocity, so the floor slows every part as it slows this one
+        if part.grounded {
+            floor = Some(part.vel);
+        }
+    }
+    let grounded = floor.is_some();
+    if let Some(floor) = floor {
+        vel.y = floor.y;
+        // unless the body has been stopped against a side of the world
+        if shift.0 == 0.0 {
+            vel.x = floor.x;
+        }
+    }
+    for &idx in group {
+        let part = &mut entities[idx];
+        part.pos.0 += shift.0;
+        part.pos.1 += shift.1;
+        part.vel = vel.clone();
+        part.grounded |= grounded;
+    }
+}
+
+/// returns how far a body must move to wrap around the world, given its centre of mass,
+/// which is zero unless it has crossed a wrapping edge.
+fn wrap_shift(com: (f32, f32), config: &WorldConfig) -> (f32, f32) {
+    let (bounds, edges) = (&config.bounds, &config.boundaries);
+    let wrap = |pos: f32, low: f32, high: f32, low_mode, high_mode| {
+        if pos < low && low_mode == BoundaryMode::Wrap {
+            high - low
+        } else if pos > high && high_mode == BoundaryMode::Wrap {
+            low - high
+        } else {
+            0.0
+        }
+    };
+    (
+        wrap(com.0, bounds.left, bounds.right, edges.left, edges.right),
+        wrap(com.1, bounds.top, bounds.bottom, edges.top, edges.bottom),
+    )
+}
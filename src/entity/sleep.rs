@@ -0,0 +1,284 @@
+use std::collections::{HashMap, HashSet};
+
+use uuid::Uuid;
+
+use super::broadphase::SpatialHash;
+use super::contact::Contact;
+use super::joints::Joint;
+use super::rigid_body::Bodies;
+use super::vector::EuclidianVector;
+use super::{Entity, EntityState, EntityType, WorldConfig};
+
+// -------------------------------------------------------------------------- //
+// ------------------------ SLEEPING AND ISLANDS ---------------------------- //
+// -------------------------------------------------------------------------- //
+
+/// how close (beyond touching) entities can be and still be in the same island,
+/// resting entities are left barely overlapping, so they may not be reporting a contact.
+const ISLAND_MARGIN: f32 = 0.1;
+
+/// returns true if the entity can be skipped by the simulation, as nothing will move it.
+pub fn at_rest(entity: &Entity) -> bool {
+    entity.id == EntityType::Static || entity.asleep
+}
+
+/// Returns the groups of (indices of) entities that are touching one another, directly or
+/// through a chain of other touching entities. The parts of a compound body, and jointed entities,
+/// are always in the same island. Statics and kinematics are never in an island, as they can't
+/// pass movement along (and kinematics never sleep), and neither are asleep entities,
+/// as they have already settled.
+pub fn islands(entities: &[Entity], joints: &[Joint]) -> Vec<Vec<usize>> {
+    // each entity starts in an island of its own, which are then merged (See "union-find")
+    let mut parent: Vec<usize> = (0..entities.len()).collect();
+    fn root(parent: &mut [usize], mut idx: usize) -> usize {
+        while parent[idx] != idx {
+            parent[idx] = parent[parent[idx]];
+            idx = parent[idx];
+        }
+        idx
+    }
+    let merge = |parent: &mut [usize], a: usize, b: usize| {
+        let (a, b) = (root(parent, a), root(parent, b));
+        // the lowest index is the root, so islands are ordered the same as the slice
+        parent[a.max(b)] = a.min(b);
+    };
+
+    let bodies = Bodies::new(entities);
+    for group in bodies.compound() {
+        for &idx in &group[1..] {
+            merge(&mut parent, group[0], idx);
+        }
+    }
+    for (a, b) in jointed(entities, joints) {
+        merge(&mut parent, a, b);
+    }
+    let settled = |entity: &Entity| entity.immovable() || entity.asleep;
+    let grid = SpatialHash::new_filtered(entities, |entity| !settled(entity));
+    for (idx, entity) in entities.iter().enumerate() {
+        if settled(entity) {
+            continue;
+        }
+        for other in grid.candidates(entity) {
+            if near(entity, &entities[other]) {
+                merge(&mut parent, idx, other);
+            }
+        }
+    }
+
+    let mut islands: Vec<Vec<usize>> = Vec::new();
+    let mut island_of: HashMap<usize, usize> = HashMap::new();
+    for (idx, entity) in entities.iter().enumerate() {
+        if settled(entity) {
+            continue;
+        }
+        let island = *island_of.entry(root(&mut parent, idx)).or_insert_with(|| {
+            islands.push(Vec::new());
+            islands.len() - 1
+        });
+        islands[island].push(idx);
+    }
+    islands
+}
+
+/// Wakes every asleep entity that is being pushed, spun or moved by something other than the
+/// simulation (e.g. user input), along with everything resting against it.
+pub(crate) fn wake_disturbed(entities: &mut [Entity], joints: &[Joint], config: &WorldConfig) {
+    let disturbed: Vec<usize> = entities
+        .iter()
+        .enumerate()
+        .filter(|(_, entity)| {
+            entity.asleep
+                && (entity.input_force.magnitude() > 0.0
+                    || entity.torque != 0.0
+                    || entity.vel.magnitude() > config.sleep_vel
+                    || entity.angular_vel.abs() > config.sleep_vel)
+        })
+        .map(|(idx, _)| idx)
+        .collect();
+    wake(entities, joints, &disturbed);
+}
+
+/// Wakes every asleep entity that some force (e.g. a gust of wind) is pushing on differently to
+/// the force it fell asleep under (see `remember_fields`), along with everything resting against it.
+/// A steady force (e.g. a gravity field) is balanced by whatever the entity settled against,
+/// so leaves it asleep.
+pub(crate) fn wake_pushed<F>(
+    entities: &mut [Entity],
+    joints: &[Joint],
+    settled: &HashMap<Uuid, EuclidianVector>,
+    force: F,
+) where
+    F: Fn(&Entity) -> EuclidianVector,
+{
+    let pushed: Vec<usize> = entities
+        .iter()
+        .enumerate()
+        .filter(|(_, entity)| {
+            let before = settled
+                .get(&entity.uuid)
+                .cloned()
+                .unwrap_or(EuclidianVector::new(0.0, 0.0));
+            entity.asleep && (force(entity) - before).magnitude() > 0.0
+        })
+        .map(|(idx, _)| idx)
+        .collect();
+    wake(entities, joints, &pushed);
+}
+
+/// Keeps track of the force (e.g. from force fields) that each asleep entity fell asleep under,
+/// forgetting the force on every entity that is awake (or gone).
+pub(crate) fn remember_fields<F>(
+    entities: &[Entity],
+    settled: &mut HashMap<Uuid, EuclidianVector>,
+    force: F,
+) where
+    F: Fn(&Entity) -> EuclidianVector,
+{
+    let asleep: HashSet<Uuid> = entities
+        .iter()
+        .filter(|entity| entity.asleep)
+        .map(|entity| entity.uuid)
+        .collect();
+    settled.retain(|uuid, _| asleep.contains(uuid));
+    for entity in entities.iter().filter(|entity| entity.asleep) {
+        settled.entry(entity.uuid).or_insert_with(|| force(entity));
+    }
+}
+
+/// Wakes every asleep entity that an awake entity has moved into,
+/// along with everything resting against it. Sensors don't wake what they touch.
+pub(crate) fn wake_touched(entities: &mut [Entity], joints: &[Joint], contacts: &[Contact]) {
+    let lookup: HashMap<Uuid, usize> = entities
+        .iter()
+        .enumerate()
+        .map(|(idx, entity)| (entity.uuid, idx))
+        .collect();
+    let touched: Vec<usize> = contacts
+        .iter()
+        .filter_map(|contact| Some((*lookup.get(&contact.a)?, *lookup.get(&contact.b)?)))
+        .filter(|&(a, b)| !entities[a].sensor && !entities[b].sensor)
+        .flat_map(|(a, b)| [(a, b), (b, a)])
+        .filter(|&(a, b)| entities[a].asleep && !at_rest(&entities[b]))
+        .map(|(a, _)| a)
+        .collect();
+    wake(entities, joints, &touched);
+}
+
+/// Wakes every asleep entity resting against any of the entities at the given indices,
+/// along with everything resting against them, as those entities are about to stop holding
+/// anything up (e.g. as they are removed from the world, or die).
+pub(crate) fn wake_around(entities: &mut [Entity], joints: &[Joint], indices: &[usize]) {
+    if indices.is_empty() {
+        return;
+    }
+    let grid = SpatialHash::new_filtered(entities, |entity| entity.asleep);
+    let mut resting: Vec<usize> = Vec::new();
+    for &idx in indices {
+        // as the entity was whilst it could still hold things up
+        let holding = Entity {
+            state: EntityState::Alive,
+            ..entities[idx].clone()
+        };
+        resting.extend(
+            grid.candidates(&holding)
+                .into_iter()
+                .filter(|&other| other != idx && near(&holding, &entities[other])),
+        );
+    }
+    wake(entities, joints, &resting);
+}
+
+/// wakes the entities at the given indices, spreading out to every asleep entity
+/// (or part of the same compound body, or jointed entity) that is touching an entity that has
+/// been woken.
+fn wake(entities: &mut [Entity], joints: &[Joint], indices: &[usize]) {
+    if indices.is_empty() {
+        return;
+    }
+    let grid = SpatialHash::new_filtered(entities, |entity| entity.asleep);
+    let bodies = Bodies::new(entities);
+    let jointed = jointed(entities, joints);
+    let mut pending: Vec<usize> = indices.to_vec();
+
+    while let Some(idx) = pending.pop() {
+        if !entities[idx].asleep {
+            continue;
+        }
+        entities[idx].asleep = false;
+        entities[idx].idle_ticks = 0;
+
+        pending.extend(bodies.members(idx));
+        pending.extend(
+            jointed
+                .iter()
+                .filter_map(|&(a, b)| (a == idx).then_some(b).or((b == idx).then_some(a))),
+        );
+        pending.extend(
+            grid.candidates(&entities[idx])
+                .into_iter()
+                .filter(|&other| entities[other].asleep && near(&entities[idx], &entities[other])),
+        );
+    }
+}
+
+/// returns the indices of each pair of (movable) entities that are connected by a joint.
+fn jointed(entities: &[Entity], joints: &[Joint]) -> Vec<(usize, usize)> {
+    let lookup: HashMap<Uuid, usize> = entities
+        .iter()
+        .enumerate()
+        .filter(|(_, entity)| !entity.immovable())
+        .map(|(idx, entity)| (entity.uuid, idx))
+        .collect();
+    joints
+        .iter()
+        .filter_map(|joint| {
+            let mut ends = joint.entities().filter_map(|uuid| lookup.get(&uuid));
+            Some((*ends.next()?, *ends.next()?))
+        })
+        .collect()
+}
+
+/// returns true if the bounding boxes of the two entities are within `ISLAND_MARGIN` of touching,
+/// and they can push one another.
+fn near(a: &Entity, b: &Entity) -> bool {
+    if a.sensor || b.sensor || !a.collides_with(b) {
+        return false;
+    }
+    let (aw, ah) = a.half_extents();
+    let (bw, bh) = b.half_extents();
+    (a.pos.0 - b.pos.0).abs() <= aw + bw + ISLAND_MARGIN
+        && (a.pos.1 - b.pos.1).abs() <= ah + bh + ISLAND_MARGIN
+}
+
+/// Counts how long each entity has been (almost) still, and puts whole islands to sleep once
+/// every entity in them has been still for long enough. Asleep entities are left out of the
+/// simulation until something wakes them.
+pub(crate) fn update(entities: &mut [Entity], joints: &[Joint], config: &WorldConfig) {
+    let Some(sleep_ticks) = config.sleep_ticks else {
+        return;
+    };
+    for entity in entities.iter_mut().filter(|entity| !at_rest(entity)) {
+        if entity.vel.magnitude() < config.sleep_vel && entity.angular_vel.abs() < config.sleep_vel
+        {
+            entity.idle_ticks = entity.idle_ticks.saturating_add(1);
+        } else {
+            entity.idle_ticks = 0;
+        }
+    }
+
+    for island in islands(entities, joints) {
+        let idle = island
+            .iter()
+            .all(|&idx| entities[idx].idle_ticks >= sleep_ticks);
+        if !idle {
+            continue;
+        }
+        for idx in island {
+            let entity = &mut entities[idx];
+            entity.asleep = true;
+            entity.vel = EuclidianVector::new(0.0, 0.0);
+            entity.acc = EuclidianVector::new(0.0, 0.0);
+            entity.angular_vel = 0.0;
+        }
+    }
+}
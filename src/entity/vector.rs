@@ -241,4 +241,38 @@ impl EuclidianVector {
     pub fn dot(&self, other: &EuclidianVector) -> f32 {
         (self.x * other.x) + (self.y * other.y)
     }
+
+    /// Returns the magnitude of the (2D) cross-product of two vectors,
+    /// which is positive if the other vector is clockwise of this one on screen (+y is down).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ascii_arcade::entity::vector::EuclidianVector;
+    ///
+    /// let right = EuclidianVector::new(1.0, 0.0);
+    /// let down = EuclidianVector::new(0.0, 1.0);
+    /// assert_eq!(right.cross(&down), 1.0);
+    /// assert_eq!(down.cross(&right), -1.0);
+    /// assert_eq!(right.cross(&right), 0.0);
+    /// ```
+    pub fn cross(&self, other: &EuclidianVector) -> f32 {
+        (self.x * other.y) - (self.y * other.x)
+    }
+
+    /// Returns the (2D) cross-product of a scalar angular velocity with this vector,
+    /// i.e. the velocity of a point at this offset from the center of rotation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ascii_arcade::entity::vector::EuclidianVector;
+    ///
+    /// // spinning clockwise on screen, the point to the right moves downwards
+    /// let v = EuclidianVector::new(2.0, 0.0).perp(3.0);
+    /// assert_eq!((v.x, v.y), (0.0, 6.0));
+    /// ```
+    pub fn perp(&self, angular_vel: f32) -> EuclidianVector {
+        EuclidianVector::new(-angular_vel * self.y, angular_vel * self.x)
+    }
 }
@@ -0,0 +1,628 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use uuid::Uuid;
+
+use super::collision::Impulses;
+use super::determinism::{self, SeededIds};
+use super::events::{ContactEvent, ContactTracker};
+use super::forces::ForceGenerator;
+use super::integrator::Integrator;
+use super::joints::{self, Joint};
+use super::kinematic;
+use super::particles::ParticleSystem;
+use super::rigid_body::Bodies;
+use super::vector::EuclidianVector;
+use super::{
+    collision, rigid_body, sleep, Entities, Entity, EntityState, EntityType, RigidBody,
+    DEFAULT_WINDOW,
+};
+
+// -------------------------------------------------------------------------- //
+// ------------------------ THE SIMULATED WORLD ----------------------------- //
+// -------------------------------------------------------------------------- //
+
+/// The rectangle that entities are kept within.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bounds {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl Bounds {
+    /// returns the bounds of a terminal window with the given (width, height),
+    /// keeping entities off the cells along its edges.
+    pub fn window(size: (u16, u16)) -> Bounds {
+        Bounds {
+            left: 1.0,
+            top: 1.0,
+            right: (size.0 - 1) as f32,
+            bottom: (size.1 - 1) as f32,
+        }
+    }
+
+    /// returns the bounds of the terminal this process is running in.
+    pub fn terminal() -> Bounds {
+        Bounds::window(termion::terminal_size().unwrap_or(DEFAULT_WINDOW))
+    }
+
+    /// returns the bounds that the centroid of the entity is kept within.
+    /// the bounds are where a single cell can go, so entities reaching further than a cell
+    /// from their centroid (e.g. wide platforms) are kept that much further inside.
+    pub fn inset_for(&self, entity: &Entity) -> Bounds {
+        let (w, h) = entity.half_extents();
+        let (dx, dy) = ((w - 0.5).max(0.0), (h - 0.5).max(0.0));
+        Bounds {
+            left: self.left + dx,
+            top: self.top + dy,
+            right: (self.right - dx).max(self.left + dx),
+            bottom: (self.bottom - dy).max(self.top + dy),
+        }
+    }
+
+    /// returns the point closest to the given position that the entity's centroid can be at.
+    pub fn clamp(&self, entity: &Entity, pos: (f32, f32)) -> (f32, f32) {
+        let inner = self.inset_for(entity);
+        (
+            pos.0.max(inner.left).min(inner.right),
+            pos.1.max(inner.top).min(inner.bottom),
+        )
+    }
+}
+
+/// What happens to entities that reach an edge of the world.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoundaryMode {
+    /// entities stop at the edge, losing their velocity into it.
+    #[default]
+    Clamp,
+    /// entities bounce off the edge without losing any speed,
+    /// unless they are barely moving into it (so resting entities stay at rest).
+    Bounce,
+    /// entities leaving through the edge reappear at the opposite edge (e.g. Asteroids, Snake).
+    Wrap,
+    /// entities leaving through the edge are removed from the world (e.g. projectiles).
+    Despawn,
+}
+
+/// The boundary mode of each edge of the world.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Boundaries {
+    pub left: BoundaryMode,
+    pub top: BoundaryMode,
+    pub right: BoundaryMode,
+    pub bottom: BoundaryMode,
+}
+
+impl Boundaries {
+    /// returns the same mode on every edge.
+    pub fn all(mode: BoundaryMode) -> Boundaries {
+        Boundaries {
+            left: mode,
+            top: mode,
+            right: mode,
+            bottom: mode,
+        }
+    }
+}
+
+/// The rules that every entity in a world is simulated by.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorldConfig {
+    /// the interval of each physics calculation, in seconds.
+    pub time_step: f32,
+
+    /// the scheme used to advance entities by each time step.
+    pub integrator: Integrator,
+
+    /// acceleration applied to every (non-static) entity, e.g. (0.0, 9.81) for a side-view
+    /// game with the earth beneath our feet, or zero for a top-down game.
+    pub gravity: EuclidianVector,
+
+    /// the limits on each component of an entity's velocity and acceleration.
+    pub max_vel: f32,
+    pub max_acc: f32,
+
+    /// the viewing area and physical boundary,
+    /// and what happens to entities reaching each of its edges.
+    pub bounds: Bounds,
+    pub boundaries: Boundaries,
+
+    /// entities moving and spinning slower than this are considered still, and fall asleep
+    /// once they (and everything touching them) have been still for some number of ticks.
+    /// set the number of ticks to `None` to keep every entity awake.
+    pub sleep_vel: f32,
+    pub sleep_ticks: Option<u32>,
+
+    /// makes runs repeatable: entities added with `PhysicsWorld::spawn` are given uuids generated
+    /// from the seed, rather than random uuids, so the same inputs always give the same results.
+    /// (entities are always simulated in the order they are stored, and nothing else is random)
+    pub seed: Option<u64>,
+}
+
+impl Default for WorldConfig {
+    fn default() -> Self {
+        Self {
+            time_step: 0.01,
+            integrator: Integrator::default(),
+            gravity: EuclidianVector::new(0.0, 0.0),
+            max_vel: 20.0,
+            max_acc: 1_000.0,
+            bounds: Bounds::window(DEFAULT_WINDOW),
+            boundaries: Boundaries::default(),
+            sleep_vel: 0.05,
+            sleep_ticks: Some(50),
+            seed: None,
+        }
+    }
+}
+
+impl WorldConfig {
+    /// returns the default settings for a side-view game, with gravity pulling everything
+    /// down the screen (at the earth's 9.81 units per second squared).
+    pub fn side_view() -> WorldConfig {
+        WorldConfig {
+            gravity: EuclidianVector::new(0.0, 9.81),
+            ..WorldConfig::default()
+        }
+    }
+}
+
+/// picks out the entities that something applies to.
+type EntityFilter = Box<dyn Fn(&Entity) -> bool>;
+
+/// called with each entity as it is added to, or removed from, a world.
+type EntityHook = Box<dyn FnMut(&Entity)>;
+
+/// A force generator registered with a world, and the entities it applies to.
+struct Registered {
+    generator: Box<dyn ForceGenerator>,
+    filter: Option<EntityFilter>,
+}
+
+/// A collection of entities, and the rules they are simulated by.
+#[derive(Default)]
+pub struct PhysicsWorld {
+    pub entities: Entities,
+    pub config: WorldConfig,
+
+    /// constraints between entities (and points in the world), solved every step.
+    pub joints: Vec<Joint>,
+
+    /// cosmetic effects (e.g. sparks or rain), moved every step but never touching entities.
+    pub particles: ParticleSystem,
+
+    /// everything else the world keeps track of, only changed through the world's methods
+    internals: WorldInternals,
+}
+
+/// The parts of a world that are managed by the world itself.
+#[derive(Default)]
+struct WorldInternals {
+    /// force fields applied every step
+    generators: Vec<Registered>,
+
+    /// the uuids given to spawned entities, when the world has a seed
+    ids: Option<SeededIds>,
+
+    /// entities (or compound bodies) to remove at the end of the next step
+    despawn_queue: Vec<Uuid>,
+    spawn_hooks: Vec<EntityHook>,
+    despawn_hooks: Vec<EntityHook>,
+
+    /// the entities that were dead or hidden at the start of the last step
+    vanished: HashSet<Uuid>,
+
+    /// the pairs of entities that were touching after the last step
+    contacts: ContactTracker,
+
+    /// what touched during the last step, until drained
+    events: Vec<ContactEvent>,
+
+    /// the impulses applied between touching entities during the last step
+    impulses: Impulses,
+
+    /// the force from the force fields on each asleep entity as it fell asleep
+    settled: HashMap<Uuid, EuclidianVector>,
+}
+
+impl PhysicsWorld {
+    pub fn new(config: WorldConfig) -> PhysicsWorld {
+        let particles = ParticleSystem::new(config.seed.unwrap_or_default());
+        PhysicsWorld {
+            entities: Vec::new(),
+            config,
+            joints: Vec::new(),
+            particles,
+            internals: WorldInternals::default(),
+        }
+    }
+
+    /// adds an entity to the world, returning its uuid, and calls the spawn hooks for it.
+    /// worlds with a seed give the entity the next of their seeded uuids.
+    pub fn spawn(&mut self, mut entity: Entity) -> Uuid {
+        if let Some(uuid) = self.next_id() {
+            entity.uuid = uuid;
+        }
+        for hook in self.internals.spawn_hooks.iter_mut() {
+            hook(&entity);
+        }
+        let uuid = entity.uuid;
+        self.entities.push(entity);
+        uuid
+    }
+
+    /// queues an entity (or every part of a compound body) to be removed from the world
+    /// at the end of the next step, so it is safe to call whilst handling that step's results.
+    pub fn despawn(&mut self, uuid: Uuid) {
+        self.internals.despawn_queue.push(uuid);
+    }
+
+    /// registers a hook, called with every entity added with `spawn` (or `spawn_body`).
+    pub fn on_spawn<F>(&mut self, hook: F)
+    where
+        F: FnMut(&Entity) + 'static,
+    {
+        self.internals.spawn_hooks.push(Box::new(hook));
+    }
+
+    /// registers a hook, called with every entity as it is removed from the world
+    /// (whether despawned, expired or having left through an edge of the world).
+    pub fn on_despawn<F>(&mut self, hook: F)
+    where
+        F: FnMut(&Entity) + 'static,
+    {
+        self.internals.despawn_hooks.push(Box::new(hook));
+    }
+
+    /// adds every part of a compound body to the world, returning the uuid of the body.
+    /// worlds with a seed give the body and its parts the next of their seeded uuids.
+    pub fn spawn_body(&mut self, mut body: RigidBody) -> Uuid {
+        if let Some(uuid) = self.next_id() {
+            body.uuid = uuid;
+        }
+        for mut part in body.parts {
+            part.body = Some(body.uuid);
+            self.spawn(part);
+        }
+        body.uuid
+    }
+
+    /// returns the next seeded uuid, if the world has a seed.
+    fn next_id(&mut self) -> Option<Uuid> {
+        let seed = self.config.seed?;
+        self.internals
+            .ids
+            .get_or_insert_with(|| SeededIds::new(seed))
+            .next()
+    }
+
+    /// returns a checksum of the state of every entity, joint and particle in the world,
+    /// including the state of the particles' random numbers (see `determinism::checksum`).
+    pub fn checksum(&self) -> u64 {
+        determinism::world_checksum(self)
+    }
+
+    /// registers a force generator, applied to every entity each step
+    /// (waking any asleep entity it pushes on differently to when it fell asleep).
+    pub fn add_generator<G>(&mut self, generator: G)
+    where
+        G: ForceGenerator + 'static,
+    {
+        self.internals.generators.push(Registered {
+            generator: Box::new(generator),
+            filter: None,
+        });
+    }
+
+    /// registers a force generator, applied to every entity that passes the filter each step
+    /// (e.g. only entities of some type, or on some collision layer).
+    pub fn add_generator_filtered<G, F>(&mut self, generator: G, filter: F)
+    where
+        G: ForceGenerator + 'static,
+        F: Fn(&Entity) -> bool + 'static,
+    {
+        self.internals.generators.push(Registered {
+            generator: Box::new(generator),
+            filter: Some(Box::new(filter)),
+        });
+    }
+
+    /// removes every registered force generator.
+    pub fn clear_generators(&mut self) {
+        self.internals.generators.clear();
+    }
+
+    /// returns the contact events from the last step (each pair of entities that began touching,
+    /// stayed touching or stopped touching), leaving none behind.
+    /// events that aren't drained are discarded by the next step.
+    pub fn drain_events(&mut self) -> Vec<ContactEvent> {
+        std::mem::take(&mut self.internals.events)
+    }
+
+    /// performs force and motion calculations on all the entities in the world,
+    /// advancing them by a single time step.
+    pub fn step(&mut self) {
+        self.wake_around_vanished();
+        let config = &self.config;
+        let entities = &mut self.entities;
+        let joints = &self.joints;
+
+        // anything pushed since the last step (or by a force field that has changed since it
+        // fell asleep) needs simulating again
+        let generators = &self.internals.generators;
+        sleep::wake_disturbed(entities, joints, config);
+        sleep::wake_pushed(entities, joints, &self.internals.settled, |entity| {
+            field_force(generators, entity, config)
+        });
+
+        // with everything asleep (or static) nothing can move, so there is nothing to simulate,
+        // and nothing new can touch (though whatever was touching still is)
+        if entities.iter().all(sleep::at_rest) {
+            self.internals.events = self.internals.contacts.update(entities, &[]);
+            self.internals.impulses = Impulses::default();
+        } else {
+            self.simulate();
+        }
+        let (config, entities) = (&self.config, &mut self.entities);
+
+        // count down lifetimes, then remove whatever has expired or left through the world's edges.
+        // (lifetimes are rounded to a whole number of steps, so rounding errors can't add a step)
+        for entity in entities.iter_mut() {
+            if let Some(ttl) = entity.ttl.as_mut() {
+                *ttl -= config.time_step;
+                if *ttl < 0.5 * config.time_step {
+                    self.internals.despawn_queue.push(entity.uuid);
+                }
+            }
+        }
+        self.internals
+            .despawn_queue
+            .extend(escaped(entities, config));
+        self.process_despawns();
+
+        self.particles.update(&self.config, &self.entities);
+    }
+
+    /// moves every entity that is awake by a single time step, resolving whatever they run into,
+    /// then puts any islands of entities that have settled to sleep.
+    fn simulate(&mut self) {
+        let config = &self.config;
+        let entities = &mut self.entities;
+        let joints = &self.joints;
+        let generators = &self.internals.generators;
+        let starts: Vec<(f32, f32)> = entities.iter().map(|entity| entity.pos).collect();
+
+        // update motion parameters based on the applied forces,
+        // holding back the entities pulled on by others until every pull has been worked out
+        let bodies = Bodies::new(entities);
+        let springs = joints::Springs::new(entities, joints);
+        let mut pulled: Vec<(usize, Entity)> = Vec::new();
+        for idx in 0..entities.len() {
+            let entity = &mut entities[idx];
+            // statics don't move, and asleep entities stay put (and grounded)
+            if sleep::at_rest(entity) {
+                continue;
+            }
+            entity.grounded = false;
+
+            // kinematics ignore every force, and just follow their paths
+            if entity.id == EntityType::Kinematic {
+                kinematic::follow(entity, config);
+                continue;
+            }
+            let gravity = entity.target_acc(config.gravity.x, config.gravity.y);
+            entity.apply_force(gravity);
+
+            // parts of compound bodies move together (a body of one part is just an entity),
+            // so are pushed by fields and springs as they are at the start of the step
+            if bodies.members(idx).len() > 1 {
+                let field = field_force(generators, &entities[idx], config);
+                let (pull, torque) = springs.pull(entities, idx, &entities[idx]);
+                let part = &mut entities[idx];
+                part.apply_force(field + pull);
+                part.apply_torque(torque);
+                continue;
+            }
+            if generators.is_empty() && !springs.attached(idx) {
+                entities[idx].update(config);
+                continue;
+            }
+
+            // fields and springs push harder or softer as the entity moves,
+            // so are worked out again at every stage of the integrator
+            let mut entity = entities[idx].clone();
+            entity.apply_torque(springs.pull(entities, idx, &entities[idx]).1);
+            let state = RefCell::new(entities[idx].clone());
+            entity.update_with(config, |pos, vel| {
+                let mut state = state.borrow_mut();
+                state.pos = pos;
+                state.vel = vel.clone();
+                field_force(generators, &state, config) + springs.pull(entities, idx, &state).0
+            });
+            pulled.push((idx, entity));
+        }
+        for (idx, entity) in pulled {
+            entities[idx] = entity;
+        }
+        rigid_body::update(entities, config);
+        let starts = unwrapped(entities, &starts, &bodies, config);
+
+        // stop bullets at the first wall along their path, before they can pass through it
+        collision::sweep(entities, &starts);
+
+        // push apart entities that moved into one another (ignoring whatever passed through the
+        // closed sides of one-way entities, or dipped into the seams between immovable entities),
+        // waking any asleep entities that were moved into
+        let contacts = collision::seams(
+            entities,
+            collision::one_way(entities, &starts, collision::detect(entities)),
+        );
+        sleep::wake_touched(entities, joints, &contacts);
+        self.internals.events = self.internals.contacts.update(entities, &contacts);
+        collision::resolve_warm(entities, &contacts, config, &mut self.internals.impulses);
+
+        // pull jointed entities back together
+        joints::solve(entities, joints);
+
+        // and back inside the world, if they were pushed out of it (e.g. through the floor)
+        rigid_body::contain(entities, config);
+
+        // put settled islands of entities to sleep
+        sleep::update(entities, joints, config);
+        sleep::remember_fields(entities, &mut self.internals.settled, |entity| {
+            field_force(generators, entity, config)
+        });
+    }
+
+    /// wakes whatever was resting on the entities that have died (or been hidden) since the last
+    /// step, as they pass through everything now, so no longer hold anything up.
+    fn wake_around_vanished(&mut self) {
+        let alive = |entity: &Entity| matches!(entity.state, EntityState::Alive);
+        let internals = &mut self.internals;
+        if internals.vanished.is_empty() && self.entities.iter().all(alive) {
+            return;
+        }
+        let vanished: Vec<usize> = (0..self.entities.len())
+            .filter(|&idx| {
+                let entity = &self.entities[idx];
+                !alive(entity) && !internals.vanished.contains(&entity.uuid)
+            })
+            .collect();
+        sleep::wake_around(&mut self.entities, &self.joints, &vanished);
+        internals.vanished = self
+            .entities
+            .iter()
+            .filter(|entity| !alive(entity))
+            .map(|entity| entity.uuid)
+            .collect();
+    }
+
+    /// removes every entity queued for despawning, in the order they are stored,
+    /// and calls the despawn hooks for each of them.
+    fn process_despawns(&mut self) {
+        if self.internals.despawn_queue.is_empty() {
+            return;
+        }
+        let queued: HashSet<Uuid> = self.internals.despawn_queue.drain(..).collect();
+        let despawned = |entity: &Entity| {
+            queued.contains(&entity.uuid) || entity.body.is_some_and(|body| queued.contains(&body))
+        };
+
+        // whatever was resting on the entities falls once they're gone
+        let leaving: Vec<usize> = (0..self.entities.len())
+            .filter(|&idx| despawned(&self.entities[idx]))
+            .collect();
+        sleep::wake_around(&mut self.entities, &self.joints, &leaving);
+
+        let (removed, kept): (Entities, Entities) = std::mem::take(&mut self.entities)
+            .into_iter()
+            .partition(despawned);
+        self.entities = kept;
+
+        // along with any joints holding on to them
+        let removed_ids: HashSet<Uuid> = removed.iter().map(|entity| entity.uuid).collect();
+        self.joints
+            .retain(|joint| joint.entities().all(|uuid| !removed_ids.contains(&uuid)));
+        for entity in removed.iter() {
+            for hook in self.internals.despawn_hooks.iter_mut() {
+                hook(entity);
+            }
+        }
+    }
+}
+
+/// returns the sum of the forces from every generator applying to the entity.
+fn field_force(
+    generators: &[Registered],
+    entity: &Entity,
+    config: &WorldConfig,
+) -> EuclidianVector {
+    generators
+        .iter()
+        .filter(|registered| {
+            registered
+                .filter
+                .as_ref()
+                .is_none_or(|filter| filter(entity))
+        })
+        .fold(EuclidianVector::new(0.0, 0.0), |sum, registered| {
+            sum + registered.generator.force(entity, config)
+        })
+}
+
+/// returns where each entity started the step, carried around the world along with any entity
+/// that has since wrapped around it, so the path each entity took this step stays short
+/// (rather than running back across the whole world).
+fn unwrapped(
+    entities: &[Entity],
+    starts: &[(f32, f32)],
+    bodies: &Bodies,
+    config: &WorldConfig,
+) -> Vec<(f32, f32)> {
+    let edges = &config.boundaries;
+    let wraps = |low: BoundaryMode, high: BoundaryMode| {
+        low == BoundaryMode::Wrap || high == BoundaryMode::Wrap
+    };
+    let (across, down) = (
+        wraps(edges.left, edges.right),
+        wraps(edges.top, edges.bottom),
+    );
+    if !across && !down {
+        return starts.to_vec();
+    }
+    // nothing travels half way across the world in a single step, without having wrapped
+    let carry = |start: f32, pos: f32, span: f32, wraps: bool| {
+        if wraps && (pos - start).abs() > span / 2.0 {
+            start + span.copysign(pos - start)
+        } else {
+            start
+        }
+    };
+    entities
+        .iter()
+        .zip(starts)
+        .enumerate()
+        .map(|(idx, (entity, start))| {
+            // single entities wrap across the bounds their centroid is kept within,
+            // and compound bodies across the world's bounds (see `rigid_body::contain`)
+            let bounds = if bodies.members(idx).len() > 1 {
+                config.bounds
+            } else {
+                config.bounds.inset_for(entity)
+            };
+            (
+                carry(start.0, entity.pos.0, bounds.right - bounds.left, across),
+                carry(start.1, entity.pos.1, bounds.bottom - bounds.top, down),
+            )
+        })
+        .collect()
+}
+
+/// returns the uuids of every entity that has passed an edge of the world that entities are
+/// despawned at. the parts of compound bodies are only returned once every part has left.
+fn escaped(entities: &[Entity], config: &WorldConfig) -> Vec<Uuid> {
+    let (bounds, edges) = (&config.bounds, &config.boundaries);
+    let escaped = |entity: &Entity| {
+        let (x, y) = entity.pos;
+        (edges.left == BoundaryMode::Despawn && x < bounds.left)
+            || (edges.right == BoundaryMode::Despawn && x > bounds.right)
+            || (edges.top == BoundaryMode::Despawn && y < bounds.top)
+            || (edges.bottom == BoundaryMode::Despawn && y > bounds.bottom)
+    };
+    if !entities.iter().any(escaped) {
+        return Vec::new();
+    }
+    let bodies = Bodies::new(entities);
+    (0..entities.len())
+        .filter(|&idx| {
+            bodies
+                .members(idx)
+                .iter()
+                .all(|&member| escaped(&entities[member]))
+        })
+        .map(|idx| entities[idx].uuid)
+        .collect()
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::f32::consts::{PI, TAU};
+use std::ops::ControlFlow;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+
+use crate::entity::particles::Particle;
+use crate::entity::{Entities, Entity, PhysicsWorld};
+
+/// the most physics ticks that are run to catch up with real time between frames,
+/// so a slow frame can't cause an ever-growing backlog of ticks (a "spiral of death").
+const MAX_TICKS_PER_FRAME: u32 = 10;
+
+/// Decides when to advance the physics and when to draw, given how much real time has passed.
+/// Physics runs at a fixed tick regardless of how long rendering takes,
+/// any time left over that is too short for a whole tick is carried over to the next frame.
+/// (See "Fix Your Timestep!", Glenn Fiedler)
+#[derive(Clone, Debug)]
+pub struct Clock {
+    tick: Duration,
+    frame: Duration,
+
+    /// real time that has passed, but has not yet been simulated
+    accumulator: Duration,
+
+    /// real time that has passed since the last frame was drawn
+    since_frame: Duration,
+}
+
+impl Clock {
+    /// creates a clock running physics ticks of some length in seconds,
+    /// and drawing some number of frames per second.
+    /// a clock with a tick that isn't positive never ticks, it only draws frames.
+    pub fn new(tick: f32, frame_rate: f32) -> Clock {
+        // rounded to whole nanoseconds, so e.g. a 10 ms tick is exactly 10 ms
+        let nanos = |secs: f32| Duration::from_nanos((secs * 1e9).round() as u64);
+        Clock {
+            tick: nanos(tick),
+            frame: nanos(1.0 / frame_rate),
+            accumulator: Duration::ZERO,
+            since_frame: Duration::ZERO,
+        }
+    }
+
+    /// accounts for some real time passing, returning how many physics ticks are now due.
+    pub fn advance(&mut self, elapsed: Duration) -> u32 {
+        self.since_frame += elapsed;
+        if self.tick.is_zero() {
+            return 0;
+        }
+        self.accumulator += elapsed;
+
+        let mut ticks = 0;
+        while self.accumulator >= self.tick {
+            self.accumulator -= self.tick;
+            ticks += 1;
+
+            // give up on catching up, rather than falling further behind
+            if ticks == MAX_TICKS_PER_FRAME {
+                self.accumulator = Duration::ZERO;
+                break;
+            }
+        }
+        ticks
+    }
+
+    /// returns true if it's time to draw another frame, and starts waiting for the next one.
+    pub fn frame_due(&mut self) -> bool {
+        if self.since_frame < self.frame {
+            return false;
+        }
+        // frames that were missed entirely are skipped, rather than drawn back-to-back
+        self.since_frame -= self.frame;
+        if self.since_frame >= self.frame {
+            self.since_frame = Duration::ZERO;
+        }
+        true
+    }
+
+    /// returns how far (0.0 to 1.0) the real time is between the last tick and the next tick.
+    pub fn alpha(&self) -> f32 {
+        if self.tick.is_zero() {
+            return 0.0;
+        }
+        self.accumulator.as_secs_f32() / self.tick.as_secs_f32()
+    }
+
+    /// returns how long until either a tick or a frame is next due.
+    pub fn until_next(&self) -> Duration {
+        let frame = self.frame.saturating_sub(self.since_frame);
+        if self.tick.is_zero() {
+            return frame;
+        }
+        let tick = self.tick.saturating_sub(self.accumulator);
+        tick.min(frame)
+    }
+}
+
+/// Everything drawn in a single frame.
+#[derive(Clone, Debug, Default)]
+pub struct Frame {
+    pub entities: Entities,
+    pub particles: Vec<Particle>,
+}
+
+/// returns the entities as they would be some fraction (alpha) of the way between
+/// their previous and current states, so motion looks smooth even when frames don't line up
+/// with physics ticks. entities that didn't exist previously are left where they are now.
+pub fn interpolate(then: &[Entity], now: &[Entity], alpha: f32) -> Entities {
+    // paired up by uuid, as removing an entity shifts every entity stored after it
+    let previously: HashMap<Uuid, &Entity> =
+        then.iter().map(|entity| (entity.uuid, entity)).collect();
+    now.iter()
+        .map(|current| {
+            let mut entity = current.clone();
+            let Some(previous) = previously.get(&current.uuid) else {
+                return entity;
+            };
+            entity.pos = (
+                previous.pos.0 + (current.pos.0 - previous.pos.0) * alpha,
+                previous.pos.1 + (current.pos.1 - previous.pos.1) * alpha,
+            );
+
+            // turn the shorter way round, rather than spinning back through a full turn
+            let turn = (current.orientation - previous.orientation + PI).rem_euclid(TAU) - PI;
+            entity.orientation = (previous.orientation + turn * alpha).rem_euclid(TAU);
+            entity
+        })
+        .collect()
+}
+
+/// Runs the world in real time until the `tick` callback breaks out of the loop.
+/// `tick` is called before every physics step (e.g. to process user input),
+/// and `draw` is called at the frame rate with the previously drawn and new frames, holding the
+/// interpolated entities and the latest particles (e.g. `scene::render_frame`).
+pub fn run<T, U, D>(world: &mut PhysicsWorld, frame_rate: f32, mut tick: U, mut draw: D) -> T
+where
+    U: FnMut(&mut PhysicsWorld) -> ControlFlow<T>,
+    D: FnMut(&Frame, &Frame),
+{
+    let mut clock = Clock::new(world.config.time_step, frame_rate);
+    let mut entities_then: Entities = world.entities.to_vec();
+    let mut drawn = Frame {
+        entities: world.entities.to_vec(),
+        particles: world.particles.particles().to_vec(),
+    };
+    let mut last = Instant::now();
+
+    loop {
+        let now = Instant::now();
+        for _ in 0..clock.advance(now - last) {
+            // capture the current state of the scene
+            entities_then = world.entities.to_vec();
+
+            if let ControlFlow::Break(result) = tick(world) {
+                return result;
+            }
+            world.step();
+        }
+        last = now;
+
+        if clock.frame_due() {
+            let frame = Frame {
+                entities: interpolate(&entities_then, &world.entities, clock.alpha()),
+                particles: world.particles.particles().to_vec(),
+            };
+            draw(&drawn, &frame);
+            drawn = frame;
+        }
+        thread::sleep(clock.until_next());
+    }
+}
@@ -1,11 +1,15 @@
-use std::{sync::mpsc::Receiver, thread, time::Duration};
+use std::{ops::ControlFlow, sync::mpsc::Receiver};
 
 use strum_macros::{EnumCount, EnumIter, FromRepr};
 use termion::event::{Event, Key, MouseButton, MouseEvent};
+use uuid::Uuid;
 
 use crate::{
-    entity::{update, vector::EuclidianVector, Entities, Entity, EntityType},
-    scene,
+    entity::{
+        forces::Drag, vector::EuclidianVector, Bounds, Emitter, Entity, EntityType, Hitbox,
+        PhysicsWorld, RigidBody, WorldConfig,
+    },
+    game_loop, scene,
     user_input::Cmd,
 };
 
@@ -17,6 +21,16 @@ pub enum Game {
     Pong,
 }
 
+/// seed of the sandbox's world, so the same inputs always play out the same way.
+const SEED: u64 = 0x5A4D_B0C5;
+
+/// fraction of the player's horizontal speed kept each tick whilst on the ground,
+/// so they come to a stop within a couple of cells once they stop moving.
+const GROUND_BRAKING: f32 = 0.9;
+
+/// index of the sandbox's emitter that throws up sparks wherever an entity is spawned.
+const SPAWN_SPARKS: usize = 0;
+
 /// Interface for the sandbox game.
 pub struct SandboxGame;
 
@@ -36,6 +50,10 @@ impl SandboxGame {
             Event::Mouse(mouse) => match mouse {
                 MouseEvent::Press(MouseButton::Left, x, y) => Cmd::SPAWN(x, y, EntityType::Npc),
                 MouseEvent::Press(MouseButton::Right, x, y) => Cmd::SPAWN(x, y, EntityType::Static),
+                MouseEvent::Press(MouseButton::Middle, x, y) => Cmd::BUILD(x, y, EntityType::Npc),
+                MouseEvent::Press(MouseButton::WheelUp, x, y) => {
+                    Cmd::BUILD(x, y, EntityType::Static)
+                }
                 _ => Cmd::DEBUG(Event::Mouse(mouse)),
             },
             _ => Cmd::DEBUG(event),
@@ -43,16 +61,20 @@ impl SandboxGame {
     }
 
     /// Apply control signals to player, and possibly modify entity pool.
-    pub fn process_cmds(player: &mut Entity, entities: &mut Entities, cmd: Cmd) -> Cmd {
+    pub fn process_cmds(player: Uuid, world: &mut PhysicsWorld, cmd: Cmd) -> Cmd {
+        let config = &world.config;
         match cmd {
             Cmd::MOVE(x, y) => {
+                let Some(player) = world.entities.iter_mut().find(|e| e.uuid == player) else {
+                    return cmd;
+                };
                 // generate movement control-force based on user-input
                 let mut move_force: EuclidianVector = if x == 0 && y != 0 {
-                    player.target_vel(player.vel.x, 8.0 * y as f32)
+                    player.target_vel(player.vel.x, 8.0 * y as f32, config)
                 } else if y == 0 && x != 0 {
-                    player.target_vel(20.0 * x as f32, player.vel.y)
+                    player.target_vel(20.0 * x as f32, player.vel.y, config)
                 } else {
-                    player.target_vel(20.0 * x as f32, 8.0 * y as f32)
+                    player.target_vel(20.0 * x as f32, 8.0 * y as f32, config)
                 };
 
                 // can only apply vertical control force when not free-falling
@@ -64,73 +86,104 @@ impl SandboxGame {
             }
             // spawn an entity of some type at some location
             Cmd::SPAWN(x, y, id) => {
-                entities.push(Entity::new(id, (x as f32, y as f32)));
+                world.spawn(Entity::new(id, (x as f32, y as f32)));
+                if let Some(sparks) = world.particles.emitters.get_mut(SPAWN_SPARKS) {
+                    sparks.pos = (x as f32, y as f32);
+                    world.particles.burst(SPAWN_SPARKS, 12);
+                }
             }
+            // spawn a compound body of some type at some location,
+            // statics make for platforms, anything else makes for a crate
+            Cmd::BUILD(x, y, id) => match id {
+                EntityType::Static => {
+                    // platforms are a single wide, flat box, which can be jumped up through
+                    let mut platform = Entity::new(id, (x as f32, y as f32));
+                    platform.hitbox = Hitbox::Aabb {
+                        half_width: 2.5,
+                        half_height: 0.5,
+                    };
+                    platform.one_way = Some(EuclidianVector::new(0.0, -1.0));
+                    platform.pos = config.bounds.clamp(&platform, platform.pos);
+                    world.spawn(platform);
+                }
+                _ => {
+                    let mut crate_body = RigidBody::rectangle(id, (x as f32, y as f32), 2, 2);
+                    crate_body.clamp(&config.bounds);
+                    world.spawn_body(crate_body);
+                }
+            },
             _ => {}
         }
         cmd
     }
 
+    /// Slows the player whilst they're on the ground, to be called every tick.
+    pub fn brake(player: Uuid, world: &mut PhysicsWorld) {
+        let config = &world.config;
+        let Some(player) = world.entities.iter_mut().find(|e| e.uuid == player) else {
+            return;
+        };
+        if player.grounded {
+            let braking = player.target_vel(player.vel.x * GROUND_BRAKING, player.vel.y, config);
+            player.apply_force(braking);
+        }
+    }
+
     /// Activate the game loop.
     pub fn play(input_reader: &Receiver<Event>) -> Cmd {
         //
         // INITIALISATION
         //
 
-        const TIME_DELTA_MS: u64 = 10;
-        let dt = Duration::from_millis(TIME_DELTA_MS).as_secs_f32();
-
-        // keep this up-to-date on every game-loop cycle so we can query the scene by coordinates
-        let mut entities_now: Entities = Vec::new();
-        let mut entities_then: Entities;
+        const FRAME_RATE: f32 = 60.0;
+
+        // a side-view world, with the earth beneath our feet, filling the terminal
+        let mut world = PhysicsWorld::new(WorldConfig {
+            bounds: Bounds::terminal(),
+            seed: Some(SEED),
+            ..WorldConfig::side_view()
+        });
+
+        // a little air resistance, so thrown crates (and the player) don't fly about too wildly
+        world.add_generator(Drag {
+            linear: 0.0,
+            quadratic: 0.02,
+        });
+
+        // sparks thrown up, then falling and fading from yellow to red, as entities are spawned
+        world.particles.add_emitter(Emitter {
+            direction: -std::f32::consts::FRAC_PI_2,
+            spread: 1.0,
+            speed: (4.0, 10.0),
+            lifetime: (0.4, 0.8),
+            ramp: vec![(255, 255, 160), (255, 200, 0), (200, 40, 0)],
+            collide: true,
+            ..Emitter::default()
+        });
 
         // player to be controlled by user
-        let player = Entity::new(EntityType::Player, (1.0, 1.0));
-        entities_now.push(player);
+        let player = world.spawn(Entity::new(EntityType::Player, (1.0, 1.0)));
 
         //
         // GAME LOOP
         //
-        '_game: loop {
-            // capture the current state of the scene
-            entities_then = entities_now.to_vec();
-
-            // extract the player from the entity pool.
-            let mut player = entities_now.pop().unwrap();
-
-            // process user input.
-            if let Ok(event) = input_reader.try_recv() {
-                let cmd = SandboxGame::parse_event(event);
-                match cmd {
-                    Cmd::EXIT | Cmd::RETURN => return cmd,
-                    _ => SandboxGame::process_cmds(&mut player, &mut entities_now, cmd),
-                };
-            };
-
-            // reinsert the player to the entity pool.
-            entities_now.push(player);
-
-            // apply global acceleration rules
-            for entity in entities_now.iter_mut() {
-                // assume the earth is beneath our feet
-                let gravity = entity.target_acc(0.0, 9.81);
-                entity.apply_force(gravity);
-
-                // simulate frictional forces
-                let friction: EuclidianVector = if entity.grounded {
-                    entity.target_vel(entity.vel.x * 0.9, entity.vel.y)
-                } else {
-                    entity.target_vel(entity.vel.x * 0.99, entity.vel.y)
+        game_loop::run(
+            &mut world,
+            FRAME_RATE,
+            |world| {
+                // process user input.
+                if let Ok(event) = input_reader.try_recv() {
+                    let cmd = SandboxGame::parse_event(event);
+                    match cmd {
+                        Cmd::EXIT | Cmd::RETURN => return ControlFlow::Break(cmd),
+                        _ => SandboxGame::process_cmds(player, world, cmd),
+                    };
                 };
-                entity.apply_force(friction);
-            }
-
-            // resolve physics calculations
-            update(&mut entities_now);
-
+                SandboxGame::brake(player, world);
+                ControlFlow::Continue(())
+            },
             // physics calculations done, render!
-            scene::render(&entities_then, &entities_now);
-            thread::sleep(Duration::from_secs_f32(dt));
-        }
+            scene::render_frame,
+        )
     }
 }
@@ -1,5 +1,6 @@
 pub mod app;
 pub mod entity;
+pub mod game_loop;
 pub mod games;
 pub mod scene;
 pub mod scene_map;
@@ -1,7 +1,11 @@
-use crate::entity::{Entity, BACKGROUND};
+use crate::entity::particles::Particle;
+use crate::entity::primitives::{Aabb, Polygon};
+use crate::entity::{Entity, Hitbox, BACKGROUND};
+use crate::game_loop::Frame;
 use ratatui::layout::Position;
 use ratatui::prelude::TermionBackend;
 use ratatui::Terminal;
+use std::collections::HashSet;
 use std::error::Error;
 use std::fmt::Debug;
 use std::io::{self, Stdout};
@@ -61,29 +65,99 @@ pub fn term_coords(pos: (f32, f32)) -> (u16, u16) {
     (x.round() as u16, y.round() as u16)
 }
 
-/// display all the entities in the scene
-pub fn render(then: &Vec<Entity>, now: &Vec<Entity>) {
-    for (old, new) in iter::zip(then, now) {
+/// returns the terminal coordinates of every cell covered by the entity.
+/// entities are drawn as a single cell, unless their hitbox is a polygon or a box,
+/// in which case every cell whose center lies within the hitbox is drawn.
+pub fn footprint(entity: &Entity) -> Vec<(u16, u16)> {
+    let polygon = match &entity.hitbox {
+        Hitbox::Polygon(vertices) => Polygon::new(&entity.pos, vertices, entity.orientation),
+        Hitbox::Aabb {
+            half_width,
+            half_height,
+        } => Polygon::from_aabb(&Aabb::new(&entity.pos, *half_width, *half_height)),
+        _ => return vec![term_coords(entity.pos)],
+    };
+    let (w, h) = entity.half_extents();
+    let (x, y) = entity.pos;
+
+    let mut cells: Vec<(u16, u16)> = Vec::new();
+    for i in (x - w).floor().max(1.0) as u16..=(x + w).ceil().max(1.0) as u16 {
+        for j in (y - h).floor().max(1.0) as u16..=(y + h).ceil().max(1.0) as u16 {
+            if polygon.contains(&(i as f32, j as f32)) {
+                cells.push((i, j));
+            }
+        }
+    }
+    if cells.is_empty() {
+        cells.push(term_coords(entity.pos));
+    }
+    cells
+}
+
+/// the character particles are drawn with, in the particle's own colour.
+pub const PARTICLE: char = '•';
+
+/// display all the particles in the scene, clearing the cells only covered by particles before.
+/// cells with more than one particle show whichever particle came last.
+pub fn render_particles(then: &[Particle], now: &[Particle]) {
+    let new_cells: Vec<(u16, u16)> = now.iter().map(|p| term_coords(p.pos)).collect();
+    for old in then {
         let (x0, y0) = term_coords(old.pos);
-        let (x1, y1) = term_coords(new.pos);
+        if !new_cells.contains(&(x0, y0)) {
+            println!("{}{}", termion::cursor::Goto(x0, y0), BACKGROUND);
+        }
+    }
+    for (particle, (x1, y1)) in iter::zip(now, new_cells) {
+        let (r, g, b) = particle.colour;
+        println!(
+            "{}{}{}{}",
+            termion::cursor::Goto(x1, y1),
+            termion::color::Fg(termion::color::Rgb(r, g, b)),
+            PARTICLE,
+            termion::color::Fg(termion::color::Reset),
+        );
+    }
+}
 
-        // clear the old position if the new position has changed
-        if (x0, y0) != (x1, y1) {
+/// display a whole frame, drawing entities over the top of any particles.
+pub fn render_frame(then: &Frame, now: &Frame) {
+    render_particles(&then.particles, &now.particles);
+    render(&then.entities, &now.entities);
+}
+
+/// returns the cells that entities covered in the previous frame but no entity covers now,
+/// including every cell of the entities that have since been removed, in drawing order.
+pub fn stale_cells(then: &[Entity], now: &[Entity]) -> Vec<(u16, u16)> {
+    let mut covered: HashSet<(u16, u16)> = now.iter().flat_map(footprint).collect();
+    // each cell is only cleared once, however many entities covered it
+    then.iter()
+        .flat_map(footprint)
+        .filter(|cell| covered.insert(*cell))
+        .collect()
+}
+
+/// display all the entities in the scene,
+/// clearing every cell that is no longer covered by an entity before drawing them.
+pub fn render(then: &[Entity], now: &[Entity]) {
+    for (x0, y0) in stale_cells(then, now) {
+        println!(
+            "{}{}",
+            // move cursor to old position
+            termion::cursor::Goto(x0, y0),
+            // clear cell
+            BACKGROUND,
+        );
+    }
+    for new in now {
+        // print the entity onto the new position
+        for (x1, y1) in footprint(new) {
             println!(
                 "{}{}",
-                // move cursor to old position
-                termion::cursor::Goto(x0, y0),
-                // clear cell
-                BACKGROUND,
+                // move cursor to new position
+                termion::cursor::Goto(x1, y1),
+                // insert entity
+                new
             );
         }
-        // print the entity onto the new position
-        println!(
-            "{}{}",
-            // move cursor to new position
-            termion::cursor::Goto(x1, y1),
-            // insert entity
-            new
-        );
     }
 }
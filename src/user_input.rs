@@ -13,6 +13,7 @@ pub enum Cmd {
     MOVE(i8, i8),
     DEBUG(Event),
     SPAWN(u16, u16, EntityType),
+    BUILD(u16, u16, EntityType),
     SELECT,
     RETURN,
     EXIT,
@@ -0,0 +1,28 @@
+// every test crate includes the whole module, but only uses some of it
+#![allow(dead_code)]
+
+use ascii_arcade::entity::{Bounds, WorldConfig};
+
+/// a side-view world 100 units across and 100 down, leaving far more room for entities
+/// to fall, jump and travel about in than the default (terminal-sized) bounds.
+pub fn open_side_view() -> WorldConfig {
+    WorldConfig {
+        bounds: Bounds {
+            left: 0.0,
+            top: 0.0,
+            right: 100.0,
+            bottom: 100.0,
+        },
+        ..WorldConfig::side_view()
+    }
+}
+
+/// returns true if the two values are within some tolerance of one another.
+pub fn approx(a: f32, b: f32, tolerance: f32) -> bool {
+    (a - b).abs() < tolerance
+}
+
+/// returns true if the two points are within some tolerance of one another, along each axis.
+pub fn approx_point(a: (f32, f32), b: (f32, f32), tolerance: f32) -> bool {
+    approx(a.0, b.0, tolerance) && approx(a.1, b.1, tolerance)
+}
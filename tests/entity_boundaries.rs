@@ -0,0 +1,203 @@
+#[cfg(test)]
+mod tests_boundaries {
+    use ascii_arcade::entity::{vector::EuclidianVector, *};
+
+    // default bounds are 1.0 to 49.0 across, and 1.0 to 9.0 down
+    fn world(boundaries: Boundaries) -> PhysicsWorld {
+        PhysicsWorld::new(WorldConfig {
+            boundaries,
+            ..Default::default()
+        })
+    }
+
+    fn moving(pos: (f32, f32), vel: (f32, f32)) -> Entity {
+        let mut entity = Entity::new(EntityType::Npc, pos);
+        entity.vel = EuclidianVector::new(vel.0, vel.1);
+        entity
+    }
+
+    #[test]
+    fn test_only_the_floor_grounds_entities() {
+        let mut world = world(Boundaries::default());
+        world.entities = vec![
+            moving((10.0, 1.05), (0.0, -10.0)),
+            moving((20.0, 8.95), (0.0, 10.0)),
+        ];
+        world.step();
+
+        let (ceiling, floor) = (&world.entities[0], &world.entities[1]);
+        assert_eq!((ceiling.pos.1, ceiling.vel.y), (1.0, 0.0));
+        assert_eq!((floor.pos.1, floor.vel.y), (9.0, 0.0));
+        assert!(!ceiling.grounded);
+        assert!(floor.grounded);
+    }
+
+    #[test]
+    fn test_wide_entities_are_kept_entirely_within_the_bounds() {
+        let mut world = world(Boundaries::default());
+        let mut plank = moving((47.5, 8.5), (10.0, 10.0));
+        plank.hitbox = Hitbox::Aabb {
+            half_width: 2.5,
+            half_height: 1.0,
+        };
+        world.entities = vec![plank];
+        world.step();
+
+        // reaching no further than a single cell at the edge would
+        let plank = &world.entities[0];
+        assert_eq!(plank.pos, (47.0, 8.5));
+        assert!(plank.grounded);
+
+        // platforms placed near an edge are moved inside it
+        let mut platform = Entity::new(EntityType::Static, (0.0, 0.0));
+        platform.hitbox = plank.hitbox.clone();
+        let bounds = world.config.bounds;
+        assert_eq!(bounds.clamp(&platform, (49.0, 5.0)), (47.0, 5.0));
+        assert_eq!(bounds.clamp(&platform, (0.0, 0.0)), (3.0, 1.5));
+        assert_eq!(bounds.clamp(&platform, (10.0, 5.0)), (10.0, 5.0));
+    }
+
+    #[test]
+    fn test_bouncing_off_the_edges() {
+        let mut world = world(Boundaries::all(BoundaryMode::Bounce));
+        world.entities = vec![moving((48.95, 5.0), (10.0, 0.0))];
+        world.step();
+
+        // mirrored back inside, heading the other way at the same speed
+        let ball = &world.entities[0];
+        assert!((ball.pos.0 - 48.95).abs() < 1e-4);
+        assert_eq!(ball.vel.x, -10.0);
+        assert!(!ball.grounded);
+
+        // but resting entities come to rest, rather than jittering
+        let mut world = PhysicsWorld::new(WorldConfig {
+            boundaries: Boundaries::all(BoundaryMode::Bounce),
+            ..WorldConfig::side_view()
+        });
+        world.entities = vec![moving((10.0, 9.0), (0.0, 0.0))];
+        for _ in 0..10 {
+            world.step();
+            assert_eq!(world.entities[0].pos.1, 9.0);
+            assert!(world.entities[0].grounded);
+        }
+    }
+
+    #[test]
+    fn test_wrapping_around_the_edges() {
+        let mut world = world(Boundaries::all(BoundaryMode::Wrap));
+        world.entities = vec![
+            moving((48.95, 5.0), (10.0, 0.0)),
+            moving((10.0, 1.05), (0.0, -10.0)),
+        ];
+        world.step();
+
+        let (right, up) = (&world.entities[0], &world.entities[1]);
+        assert!((right.pos.0 - 1.05).abs() < 1e-4);
+        assert_eq!(right.vel.x, 10.0);
+        assert!((up.pos.1 - 8.95).abs() < 1e-4);
+        assert_eq!(up.vel.y, -10.0);
+        assert!(!up.grounded);
+    }
+
+    #[test]
+    fn test_bullets_wrapping_around_the_edges_are_not_swept_across_the_world() {
+        let mut world = world(Boundaries::all(BoundaryMode::Wrap));
+        let mut bullet = moving((48.95, 5.0), (10.0, 0.0));
+        bullet.bullet = true;
+        world.entities = vec![bullet, Entity::new(EntityType::Static, (20.0, 5.0))];
+        world.step();
+
+        // carried on around, rather than stopped by the wall between where it left and came back
+        let bullet = &world.entities[0];
+        assert!((bullet.pos.0 - 1.05).abs() < 1e-4);
+        assert_eq!(bullet.vel.x, 10.0);
+
+        // and still bounced back by walls it reaches after wrapping
+        let mut bullet = moving((48.95, 5.0), (100.0, 0.0));
+        bullet.bullet = true;
+        world.config.max_vel = 1_000.0;
+        world.entities = vec![bullet, Entity::new(EntityType::Static, (1.5, 5.0))];
+        world.step();
+        assert!(world.entities[0].vel.x < 0.0);
+    }
+
+    #[test]
+    fn test_bodies_wrap_around_the_edges_as_a_whole() {
+        let mut world = world(Boundaries::all(BoundaryMode::Wrap));
+        let mut body = RigidBody::rectangle(EntityType::Npc, (45.0, 5.0), 3, 1);
+        for part in body.parts.iter_mut() {
+            part.vel = EuclidianVector::new(10.0, 0.0);
+        }
+        world.spawn_body(body);
+        // just inside the opposite edge, where a part wrapping on its own would land
+        world.spawn(Entity::new(EntityType::Static, (1.5, 5.0)));
+
+        let com = |world: &PhysicsWorld| {
+            world.entities[..3]
+                .iter()
+                .map(|part| part.pos.0)
+                .sum::<f32>()
+                / 3.0
+        };
+        let mut last = com(&world);
+        for tick in 0..100 {
+            // the wall is cleared away before the body itself wraps around onto it
+            if tick == 25 {
+                world.entities.pop();
+            }
+            world.step();
+
+            // the body moves steadily on, once wrapping (by the width of the world) as it goes
+            let moved = com(&world) - last;
+            assert!((moved - 0.1).abs() < 1e-3 || (moved - 0.1 + 48.0).abs() < 1e-3);
+            assert_eq!(world.entities[0].vel.x, 10.0);
+            last = com(&world);
+
+            // without its parts being torn apart
+            let xs: Vec<f32> = world.entities[..3].iter().map(|part| part.pos.0).collect();
+            assert!((xs[1] - xs[0] - 1.0).abs() < 1e-3 && (xs[2] - xs[1] - 1.0).abs() < 1e-3);
+        }
+        assert!((com(&world) - 8.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_despawning_at_the_edges() {
+        // a shooting gallery, where the floor still holds things up
+        let mut world = world(Boundaries {
+            bottom: BoundaryMode::Clamp,
+            ..Boundaries::all(BoundaryMode::Despawn)
+        });
+        let shot = moving((45.0, 5.0), (10.0, 0.0));
+        let shot_id = shot.uuid;
+        world.entities = vec![shot, moving((20.0, 8.95), (0.0, 10.0))];
+        for _ in 0..40 {
+            world.step();
+            assert!(world.entities.iter().any(|e| e.uuid == shot_id));
+        }
+        world.step();
+        assert!(world.entities.iter().all(|e| e.uuid != shot_id));
+        assert_eq!(world.entities.len(), 1);
+        assert!(world.entities[0].grounded);
+    }
+
+    #[test]
+    fn test_bodies_despawn_once_entirely_gone() {
+        let mut world = world(Boundaries::all(BoundaryMode::Despawn));
+        world.spawn_body(RigidBody::rectangle(EntityType::Npc, (47.0, 5.0), 2, 1));
+        for part in world.entities.iter_mut() {
+            part.vel.x = 10.0;
+        }
+
+        // one part has left, but the other hasn't yet
+        for _ in 0..15 {
+            world.step();
+        }
+        assert_eq!(world.entities.len(), 2);
+        assert!(world.entities[1].pos.0 > 49.0 && world.entities[0].pos.0 < 49.0);
+
+        for _ in 0..10 {
+            world.step();
+        }
+        assert!(world.entities.is_empty());
+    }
+}
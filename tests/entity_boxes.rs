@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod test_entity_boxes {
+    use ascii_arcade::entity::*;
+
+    fn platform(pos: (f32, f32), half_width: f32, half_height: f32) -> Entity {
+        let mut platform = Entity::new(EntityType::Static, pos);
+        platform.hitbox = Hitbox::Aabb {
+            half_width,
+            half_height,
+        };
+        platform
+    }
+
+    #[test]
+    fn test_entities_land_anywhere_along_a_platform() {
+        for x in [6.0, 10.0, 13.5] {
+            let mut world = PhysicsWorld::new(WorldConfig::side_view());
+            world.entities = vec![
+                platform((10.0, 7.0), 4.0, 0.5),
+                Entity::new(EntityType::Npc, (x, 3.0)),
+            ];
+            for _ in 0..200 {
+                world.step();
+            }
+            // resting on the top of the platform, rather than its centroid's square
+            assert!((world.entities[1].pos.1 - 6.0).abs() < 0.05);
+        }
+
+        // just beyond the end of the platform it falls straight past
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        world.entities = vec![
+            platform((10.0, 7.0), 4.0, 0.5),
+            Entity::new(EntityType::Npc, (14.6, 3.0)),
+        ];
+        for _ in 0..200 {
+            world.step();
+        }
+        assert!(world.entities[1].pos.1 > 7.0);
+    }
+
+    #[test]
+    fn test_entities_bounce_off_tall_walls() {
+        let mut world = PhysicsWorld::default();
+        world.entities = vec![
+            platform((10.0, 5.0), 0.5, 4.0),
+            Entity::new(EntityType::Npc, (6.0, 2.0)),
+        ];
+        world.entities[1].vel.x = 10.0;
+        for _ in 0..100 {
+            world.step();
+        }
+        // stopped by the wall well above its centroid, then sent back the way it came
+        assert!(world.entities[1].vel.x < 0.0);
+        assert!(world.entities[1].pos.0 < 9.0);
+    }
+}
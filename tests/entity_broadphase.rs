@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests_broadphase {
+    use ascii_arcade::entity::{broadphase::*, collision, contact, *};
+
+    /// creates a crowded scene of npcs piled up above a static floor
+    fn make_scene() -> Vec<Entity> {
+        let mut entities = Vec::new();
+        for x in 1..=40 {
+            entities.push(Entity::new(EntityType::Static, (x as f32, 8.0)));
+        }
+        for i in 0..200 {
+            let x = 2.0 + (i % 37) as f32 + 0.1 * (i % 7) as f32;
+            let y = 1.0 + (i % 6) as f32 + 0.05 * (i % 11) as f32;
+            let mut npc = Entity::new(EntityType::Npc, (x, y));
+            npc.vel.x = (i % 5) as f32 * 3.0 - 6.0;
+            npc.vel.y = (i % 3) as f32 * 6.0 - 6.0;
+            entities.push(npc);
+        }
+        entities
+    }
+
+    /// compares every entity with every other entity to find the contacts between them
+    fn brute_force_detect(entities: &[Entity]) -> Vec<contact::Contact> {
+        let mut contacts = Vec::new();
+        for (idx, entity) in entities.iter().enumerate() {
+            for other in &entities[idx + 1..] {
+                if let Some(contact) = collision::narrowphase(entity, other) {
+                    contacts.push(contact);
+                }
+            }
+        }
+        contacts
+    }
+
+    #[test]
+    fn test_broadphase_matches_brute_force() {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        world.entities = make_scene();
+        // asleep entities aren't checked against one another, unlike in a brute-force scan
+        world.config.sleep_ticks = None;
+
+        // let the scene evolve so the contacts vary between updates
+        for _ in 0..200 {
+            world.step();
+
+            let expected = brute_force_detect(&world.entities);
+            let actual = collision::detect(&world.entities);
+            assert!(!expected.is_empty());
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_candidates_only_include_nearby_entities() {
+        let entities = vec![
+            Entity::new(EntityType::Npc, (5.0, 5.0)),
+            Entity::new(EntityType::Npc, (5.9, 5.0)),
+            Entity::new(EntityType::Npc, (5.0, 4.2)),
+            Entity::new(EntityType::Npc, (9.0, 5.0)),
+            Entity::new(EntityType::Npc, (5.0, 9.0)),
+        ];
+        let grid = SpatialHash::new(&entities);
+        assert_eq!(grid.candidates(&entities[0]), vec![0, 1, 2]);
+        assert_eq!(grid.candidates(&entities[3]), vec![3]);
+        assert_eq!(grid.candidates(&entities[4]), vec![4]);
+
+        // e.g. everything along a path from one entity to another
+        assert_eq!(grid.within((5.0, 5.0), (9.0, 5.0)), vec![0, 1, 2, 3]);
+        assert_eq!(grid.within((6.6, 6.6), (8.4, 8.4)), Vec::<usize>::new());
+    }
+}
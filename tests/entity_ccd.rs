@@ -0,0 +1,152 @@
+#[cfg(test)]
+mod tests_continuous_collisions {
+    use ascii_arcade::entity::*;
+
+    /// a wall much thinner than the distance travelled in one step at top speed.
+    fn thin_wall(hitbox: Hitbox) -> Entity {
+        let mut wall = Entity::new(EntityType::Static, (10.0, 5.0));
+        wall.hitbox = hitbox;
+        wall
+    }
+
+    /// a small entity heading to the right at top speed.
+    fn projectile(x: f32, bullet: bool) -> Entity {
+        let mut projectile = Entity::new(EntityType::Npc, (x, 5.0));
+        projectile.hit_radius = 0.1;
+        projectile.vel.x = 20.0;
+        projectile.bullet = bullet;
+        projectile
+    }
+
+    /// returns how many of a spread of projectiles end up on the far side of the wall.
+    fn count_tunnelled(wall: &Entity, bullet: bool) -> usize {
+        (0..20)
+            .filter(|offset| {
+                let mut world = PhysicsWorld::default();
+                world.entities = vec![
+                    wall.clone(),
+                    projectile(8.0 + 0.01 * *offset as f32, bullet),
+                ];
+                for _ in 0..50 {
+                    world.step();
+                }
+                world.entities[1].pos.0 > wall.pos.0
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_fast_entities_tunnel_through_thin_walls() {
+        let wall = thin_wall(Hitbox::Aabb {
+            half_width: 0.05,
+            half_height: 3.0,
+        });
+        assert!(count_tunnelled(&wall, false) > 0);
+    }
+
+    #[test]
+    fn test_bullets_never_tunnel_through_thin_walls() {
+        let walls = [
+            thin_wall(Hitbox::Aabb {
+                half_width: 0.05,
+                half_height: 3.0,
+            }),
+            thin_wall(Hitbox::Polygon(vec![
+                (-0.05, -3.0),
+                (0.05, -3.0),
+                (0.05, 3.0),
+                (-0.05, 3.0),
+            ])),
+            thin_wall(Hitbox::Circle),
+        ];
+        for wall in walls.iter() {
+            assert_eq!(count_tunnelled(wall, true), 0);
+        }
+    }
+
+    #[test]
+    fn test_bullets_bounce_off_walls() {
+        let wall = thin_wall(Hitbox::Aabb {
+            half_width: 0.05,
+            half_height: 3.0,
+        });
+        let mut world = PhysicsWorld::default();
+        world.entities = vec![wall, projectile(9.0, true)];
+        let mut stopped = None;
+        for _ in 0..10 {
+            world.step();
+            if stopped.is_none() && world.entities[1].vel.x < 0.0 {
+                stopped = Some(world.entities[1].pos.0);
+            }
+        }
+
+        // stopped touching the wall, and sent back at half speed (an npc's restitution)
+        assert!((stopped.unwrap() - (10.0 - 0.05 - 0.1)).abs() < 1e-4);
+        assert!((world.entities[1].vel.x + 10.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_bullets_land_on_thin_floors() {
+        let mut floor = Entity::new(EntityType::Static, (10.0, 8.0));
+        floor.hitbox = Hitbox::Aabb {
+            half_width: 5.0,
+            half_height: 0.05,
+        };
+        let mut ball = Entity::new(EntityType::Npc, (9.0, 7.0));
+        ball.hit_radius = 0.1;
+        ball.restitution = 0.0;
+        ball.vel = vector::EuclidianVector::new(3.0, 20.0);
+        ball.bullet = true;
+        let mut world = PhysicsWorld::default();
+        world.entities = vec![floor, ball];
+
+        for _ in 0..5 {
+            world.step();
+        }
+
+        // landed on top of the floor, still sliding along it
+        assert!(world.entities[1].pos.1 < 8.0);
+        assert!(world.entities[1].vel.y.abs() < 1e-3);
+        assert!(world.entities[1].vel.x > 0.0);
+    }
+
+    #[test]
+    fn test_bullets_touching_walls_do_not_tunnel_through_them() {
+        let wall = thin_wall(Hitbox::Aabb {
+            half_width: 0.05,
+            half_height: 3.0,
+        });
+        // starting (just) overlapping the wall, heading into it
+        let mut projectile = projectile(10.0 - 0.05 - 0.09, true);
+        projectile.vel.x = 500.0;
+        let mut world = PhysicsWorld::default();
+        world.config.max_vel = 1_000.0;
+        world.entities = vec![wall, projectile];
+        for _ in 0..10 {
+            world.step();
+            assert!(world.entities[1].pos.0 < 10.0);
+        }
+    }
+
+    #[test]
+    fn test_bullets_slide_along_walls_they_touch() {
+        let mut floor = Entity::new(EntityType::Static, (10.0, 8.0));
+        floor.hitbox = Hitbox::Aabb {
+            half_width: 5.0,
+            half_height: 0.05,
+        };
+        // resting on the floor, and pressed into it
+        let mut ball = projectile(9.0, true);
+        ball.pos.1 = 8.0 - 0.05 - 0.1;
+        ball.vel.y = 20.0;
+        let mut world = PhysicsWorld::default();
+        world.entities = vec![floor, ball];
+
+        world.step();
+        let ball = &world.entities[1];
+        assert!((ball.pos.0 - 9.2).abs() < 1e-4);
+        assert!((ball.pos.1 - (8.0 - 0.05 - 0.1)).abs() < 1e-4);
+        assert!(ball.vel.y <= 0.0);
+        assert!(ball.grounded);
+    }
+}
@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod test_entity_round_collisions {
+    use ascii_arcade::entity::*;
+
+    #[test]
+    fn test_ball_bounces_along_contact_normal() {
+        // a ball moving right clips the top-left of a static ball
+        let mut ball = Entity::new(EntityType::Npc, (5.0, 5.0));
+        ball.hitbox = Hitbox::Circle;
+        ball.restitution = 1.0;
+        (ball.static_friction, ball.kinetic_friction) = (0.0, 0.0);
+        ball.vel.x = 15.0;
+        let mut bumper = Entity::new(EntityType::Static, (5.6, 5.6));
+        bumper.hitbox = Hitbox::Circle;
+
+        let mut world = PhysicsWorld::default();
+        world.entities = vec![ball, bumper];
+        world.step();
+
+        // deflected upwards and slowed horizontally, rather than simply reversing along x
+        let ball = &world.entities[0];
+        assert!(ball.vel.y < 0.0);
+        assert!(ball.vel.x.abs() < 15.0);
+        // speed is conserved when perfectly bouncing off of a static without friction
+        let speed = ball.vel.magnitude();
+        assert!((speed - 15.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_square_hitboxes_snap_to_dominant_axis() {
+        let mut a = Entity::new(EntityType::Npc, (5.0, 5.0));
+        a.vel.x = 15.0;
+        let b = Entity::new(EntityType::Npc, (5.6, 5.6));
+
+        let mut world = PhysicsWorld::default();
+        world.entities = vec![a, b];
+        world.step();
+        assert_eq!(world.entities[0].vel.y, 0.0);
+    }
+}
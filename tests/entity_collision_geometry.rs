@@ -1,3 +1,5 @@
+mod common;
+
 #[cfg(test)]
 mod tests_angle {
     use ascii_arcade::entity::angles::*;
@@ -218,4 +220,334 @@ mod tests_rectangle {
         assert!(rect_ref.overlap(&rect_btm_right).unwrap() == (1.0, 1.0));
         assert!(rect_ref.overlap(&rect_no_overlap).is_none());
     }
+
+    #[test]
+    fn test_square_contact_normal_is_orthogonal_to_least_overlap() {
+        let rect_ref = Square::new(&(0.0, 0.0), &1.0);
+
+        // mostly overlapping along x, so separate vertically
+        let contact = rect_ref.contact(&Square::new(&(0.5, 1.5), &1.0)).unwrap();
+        assert_eq!((contact.normal.x, contact.normal.y), (0.0, 1.0));
+        assert_eq!(contact.depth, 0.5);
+        assert_eq!(contact.points, vec![(-0.5, 0.75), (1.0, 0.75)]);
+
+        // mostly overlapping along y, so separate horizontally
+        let contact = rect_ref.contact(&Square::new(&(-1.5, 0.5), &1.0)).unwrap();
+        assert_eq!((contact.normal.x, contact.normal.y), (-1.0, 0.0));
+        assert_eq!(contact.depth, 0.5);
+        assert_eq!(contact.points, vec![(-0.75, -0.5), (-0.75, 1.0)]);
+
+        assert!(rect_ref
+            .contact(&Square::new(&(10.0, 10.0), &1.0))
+            .is_none());
+    }
+
+    #[test]
+    fn test_square_contact_separates_along_both_axes_on_a_tie() {
+        let rect_ref = Square::new(&(0.0, 0.0), &1.0);
+
+        // overlapping equally along both axes, corner to corner
+        let contact = rect_ref.contact(&Square::new(&(1.5, 1.5), &1.0)).unwrap();
+        let diagonal = std::f32::consts::FRAC_1_SQRT_2;
+        assert!((contact.normal.x - diagonal).abs() < 1e-6);
+        assert!((contact.normal.y - diagonal).abs() < 1e-6);
+        // moving along the normal by the depth undoes the overlap along both axes
+        assert!((contact.depth - 0.5 * 2f32.sqrt()).abs() < 1e-6);
+        assert_eq!(contact.points, vec![(0.75, 0.75)]);
+    }
+}
+
+#[cfg(test)]
+mod tests_contacts {
+    use ascii_arcade::entity::{collision, *};
+
+    #[test]
+    fn test_detect_reports_each_touching_pair_once() {
+        let mut ball = Entity::new(EntityType::Npc, (3.0, 3.9));
+        ball.hitbox = Hitbox::Circle;
+        let entities = vec![
+            Entity::new(EntityType::Static, (3.0, 3.0)),
+            ball,
+            Entity::new(EntityType::Npc, (3.8, 3.0)),
+            Entity::new(EntityType::Npc, (8.0, 8.0)),
+        ];
+
+        let contacts = collision::detect(&entities);
+        assert_eq!(contacts.len(), 2);
+
+        // the circle beneath the square
+        assert_eq!(
+            (contacts[0].a, contacts[0].b),
+            (entities[0].uuid, entities[1].uuid)
+        );
+        assert_eq!(contacts[0].manifold.normal.y, 1.0);
+
+        // the two squares side-by-side
+        assert_eq!(
+            (contacts[1].a, contacts[1].b),
+            (entities[0].uuid, entities[2].uuid)
+        );
+        assert_eq!(contacts[1].manifold.normal.x, 1.0);
+    }
+}
+
+#[cfg(test)]
+mod tests_circle {
+    use ascii_arcade::entity::primitives::*;
+
+    #[test]
+    fn test_circles_intersect_by_euclidean_distance() {
+        let circle_ref = Circle::new(&(0.0, 0.0), &1.0);
+
+        // within the bounding boxes, but too far apart along the diagonal
+        assert!(!circle_ref.intersects(&Circle::new(&(1.5, 1.5), &1.0)));
+        assert!(circle_ref
+            .contact(&Circle::new(&(1.5, 1.5), &1.0))
+            .is_none());
+
+        assert!(circle_ref.intersects(&Circle::new(&(1.0, 1.0), &1.0)));
+        assert!(circle_ref.intersects(&Circle::new(&(2.0, 0.0), &1.0)));
+    }
+
+    #[test]
+    fn test_circle_contact_normal_and_depth() {
+        let circle_ref = Circle::new(&(0.0, 0.0), &1.0);
+
+        let contact = circle_ref.contact(&Circle::new(&(3.0, 4.0), &4.5)).unwrap();
+        assert_eq!((contact.normal.x, contact.normal.y), (0.6, 0.8));
+        assert_eq!(contact.depth, 0.5);
+
+        let contact = circle_ref
+            .contact(&Circle::new(&(-1.5, 0.0), &1.0))
+            .unwrap();
+        assert_eq!((contact.normal.x, contact.normal.y), (-1.0, 0.0));
+        assert_eq!(contact.depth, 0.5);
+        assert_eq!(contact.points, vec![(-0.75, 0.0)]);
+    }
+
+    #[test]
+    fn test_circle_square_contact_normal_and_depth() {
+        let square = Square::new(&(0.0, 0.0), &1.0);
+
+        // touching a side
+        let contact = Circle::new(&(0.0, -1.5), &1.0)
+            .contact_square(&square)
+            .unwrap();
+        assert_eq!((contact.normal.x, contact.normal.y), (0.0, 1.0));
+        assert_eq!(contact.depth, 0.5);
+        assert_eq!(contact.points, vec![(0.0, -1.0)]);
+
+        // touching a corner
+        let contact = Circle::new(&(1.6, 1.8), &1.5)
+            .contact_square(&square)
+            .unwrap();
+        assert!((contact.normal.x + 0.6).abs() < 1e-5);
+        assert!((contact.normal.y + 0.8).abs() < 1e-5);
+        assert!((contact.depth - 0.5).abs() < 1e-5);
+        assert_eq!(contact.points, vec![(1.0, 1.0)]);
+
+        // just beyond the corner, despite being within the bounding box
+        assert!(Circle::new(&(1.6, 1.8), &0.9)
+            .contact_square(&square)
+            .is_none());
+
+        // centroid inside of the square
+        let contact = Circle::new(&(0.0, 0.75), &0.5)
+            .contact_square(&square)
+            .unwrap();
+        assert_eq!((contact.normal.x, contact.normal.y), (0.0, -1.0));
+        assert_eq!(contact.depth, 0.75);
+    }
+}
+
+#[cfg(test)]
+mod tests_polygon {
+    use crate::common::approx;
+    use ascii_arcade::entity::primitives::*;
+    use std::f32::consts::FRAC_PI_4;
+
+    const UNIT_SQUARE: [(f32, f32); 4] = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)];
+
+    #[test]
+    fn test_polygon_vertices_are_rotated_about_centroid() {
+        let polygon = Polygon::new(&(5.0, 5.0), &[(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0)], 0.0);
+        assert_eq!(polygon.vertices(), &[(6.0, 5.0), (5.0, 6.0), (4.0, 5.0)]);
+
+        // a quarter turn clockwise (on screen) maps right onto down
+        let polygon = Polygon::new(&(5.0, 5.0), &[(1.0, 0.0)], std::f32::consts::FRAC_PI_2);
+        let (x, y) = polygon.vertices()[0];
+        assert!(approx(x, 5.0, 1e-4) && approx(y, 6.0, 1e-4));
+    }
+
+    #[test]
+    fn test_polygon_contains_point() {
+        let triangle = Polygon::new(&(0.0, 0.0), &[(0.0, -2.0), (2.0, 2.0), (-2.0, 2.0)], 0.0);
+        assert!(triangle.contains(&(0.0, 0.0)));
+        assert!(triangle.contains(&(0.0, 2.0)));
+        assert!(!triangle.contains(&(1.5, -1.0)));
+        assert!(!triangle.contains(&(0.0, 2.5)));
+    }
+
+    #[test]
+    fn test_polygons_without_three_vertices_contain_nothing() {
+        for vertices in [vec![], vec![(0.0, 0.0)], vec![(-1.0, 0.0), (1.0, 0.0)]] {
+            let polygon = Polygon::new(&(0.0, 0.0), &vertices, 0.0);
+            assert!(!polygon.contains(&(0.0, 0.0)));
+            assert!(!polygon.contains(&(5.0, 5.0)));
+        }
+    }
+
+    #[test]
+    fn test_polygon_separating_axis() {
+        let square = Polygon::new(&(0.0, 0.0), &UNIT_SQUARE, 0.0);
+
+        // a diamond just beyond the corner of the square, despite overlapping bounding boxes
+        let diamond = Polygon::new(&(2.3, 2.3), &UNIT_SQUARE, FRAC_PI_4);
+        assert!(square.contact(&diamond).is_none());
+
+        // the diamond's corner poking into the side of the square
+        let diamond = Polygon::new(&(2.2, 0.0), &UNIT_SQUARE, FRAC_PI_4);
+        let contact = square.contact(&diamond).unwrap();
+        assert!(approx(contact.normal.x, 1.0, 1e-4) && approx(contact.normal.y, 0.0, 1e-4));
+        assert!(approx(contact.depth, 2.0_f32.sqrt() - 1.2, 1e-4));
+        assert_eq!(contact.points.len(), 1);
+        assert!(approx(contact.points[0].0, 2.2 - 2.0_f32.sqrt(), 1e-4));
+        assert!(approx(contact.points[0].1, 0.0, 1e-4));
+    }
+
+    #[test]
+    fn test_polygons_with_repeated_vertices_still_collide() {
+        let square = Polygon::new(&(0.0, 0.0), &UNIT_SQUARE, 0.0);
+        let repeated = [
+            (-1.0, -1.0),
+            (1.0, -1.0),
+            (1.0, -1.0),
+            (1.0, 1.0),
+            (-1.0, 1.0),
+            (-1.0, 1.0),
+        ];
+        let other = Polygon::new(&(1.5, 0.0), &repeated, 0.0);
+        let expect = square.contact(&Polygon::new(&(1.5, 0.0), &UNIT_SQUARE, 0.0));
+        let contact = square.contact(&other).unwrap();
+        assert_eq!(contact.normal, expect.as_ref().unwrap().normal);
+        assert!(approx(contact.depth, 0.5, 1e-4));
+        assert!(other.contact(&square).is_some());
+
+        let circle = Circle::new(&(0.0, -1.5), &1.0);
+        assert!(Polygon::new(&(0.0, 0.0), &repeated, 0.0)
+            .contact_circle(&circle)
+            .is_some());
+    }
+
+    #[test]
+    fn test_polygon_matches_square_contact() {
+        let a = Square::new(&(0.0, 0.0), &1.0);
+        let b = Square::new(&(0.5, 1.5), &1.0);
+        let expect = a.contact(&b).unwrap();
+        let actual = Polygon::from_square(&a)
+            .contact(&Polygon::from_square(&b))
+            .unwrap();
+        assert_eq!(expect.normal, actual.normal);
+        assert_eq!(expect.depth, actual.depth);
+    }
+
+    #[test]
+    fn test_polygon_circle_contact() {
+        let square = Polygon::new(&(0.0, 0.0), &UNIT_SQUARE, 0.0);
+
+        // resting on top of a side
+        let contact = square
+            .contact_circle(&Circle::new(&(0.0, -1.5), &1.0))
+            .unwrap();
+        assert_eq!((contact.normal.x, contact.normal.y), (0.0, -1.0));
+        assert!(approx(contact.depth, 0.5, 1e-4));
+        assert_eq!(contact.points, vec![(0.0, -0.5)]);
+
+        // touching a corner
+        let contact = square
+            .contact_circle(&Circle::new(&(1.6, 1.8), &1.5))
+            .unwrap();
+        assert!(approx(contact.normal.x, 0.6, 1e-4) && approx(contact.normal.y, 0.8, 1e-4));
+        assert!(approx(contact.depth, 0.5, 1e-4));
+
+        // just beyond the corner
+        assert!(square
+            .contact_circle(&Circle::new(&(1.6, 1.8), &0.9))
+            .is_none());
+    }
+}
+
+#[cfg(test)]
+mod tests_aabb {
+    use ascii_arcade::entity::primitives::*;
+
+    #[test]
+    fn test_aabb_overlap_respects_both_extents() {
+        let paddle = Aabb::new(&(0.0, 0.0), 3.0, 0.5);
+
+        // within reach of the paddle's width, but above its height
+        assert_eq!(paddle.overlap(&Aabb::new(&(2.0, -1.2), 0.5, 0.5)), None);
+
+        // overlapping the end of the paddle
+        assert_eq!(
+            paddle.overlap(&Aabb::new(&(3.0, 0.5), 0.5, 0.5)),
+            Some((0.5, 0.5))
+        );
+        assert!(paddle.contains(&(2.9, 0.4)));
+        assert!(!paddle.contains(&(2.9, 0.6)));
+    }
+
+    #[test]
+    fn test_aabb_contact() {
+        // a box landing on a wide floor is pushed out of the top
+        let floor = Aabb::new(&(0.0, 5.0), 10.0, 1.0);
+        let contact = floor.contact(&Aabb::new(&(4.0, 3.75), 0.5, 0.5)).unwrap();
+        assert_eq!((contact.normal.x, contact.normal.y), (0.0, -1.0));
+        assert_eq!(contact.depth, 0.25);
+        assert_eq!(contact.points, vec![(3.5, 4.125), (4.5, 4.125)]);
+
+        // a box hitting a tall wall is pushed out of the side
+        let wall = Aabb::new(&(0.0, 0.0), 0.5, 10.0);
+        let contact = wall.contact(&Aabb::new(&(-0.75, 3.0), 0.5, 0.5)).unwrap();
+        assert_eq!((contact.normal.x, contact.normal.y), (-1.0, 0.0));
+        assert_eq!(contact.depth, 0.25);
+    }
+
+    #[test]
+    fn test_aabb_matches_square_contact() {
+        let a = Square::new(&(0.0, 0.0), &1.0);
+        let b = Square::new(&(0.5, 1.5), &1.0);
+        let expect = a.contact(&b).unwrap();
+        let actual = Aabb::from_square(&a)
+            .contact(&Aabb::from_square(&b))
+            .unwrap();
+        assert_eq!(expect.normal, actual.normal);
+        assert_eq!(expect.depth, actual.depth);
+        assert_eq!(expect.points, actual.points);
+    }
+
+    #[test]
+    fn test_aabb_circle_contact() {
+        let floor = Aabb::new(&(0.0, 0.0), 4.0, 1.0);
+
+        // resting on the top, far from the center
+        let contact = Circle::new(&(3.0, -1.5), &1.0)
+            .contact_aabb(&floor)
+            .unwrap();
+        assert_eq!((contact.normal.x, contact.normal.y), (0.0, 1.0));
+        assert_eq!(contact.depth, 0.5);
+        assert_eq!(contact.points, vec![(3.0, -1.0)]);
+
+        // the same circle would be clear of a square with the floor's height
+        assert!(Circle::new(&(3.0, -1.5), &1.0)
+            .contact_square(&Square::new(&(0.0, 0.0), &1.0))
+            .is_none());
+
+        // centroid inside the box, pushed out of the nearest (top) side
+        let contact = Circle::new(&(-2.0, -0.5), &0.5)
+            .contact_aabb(&floor)
+            .unwrap();
+        assert_eq!((contact.normal.x, contact.normal.y), (0.0, 1.0));
+        assert_eq!(contact.depth, 1.0);
+    }
 }
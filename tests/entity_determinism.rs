@@ -0,0 +1,217 @@
+#[cfg(test)]
+mod tests_determinism {
+    use ascii_arcade::entity::determinism::{checksum, Recording, SeededIds};
+    use ascii_arcade::entity::{vector::EuclidianVector, *};
+    use uuid::Uuid;
+
+    /// what the player did before a tick
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Input {
+        Idle,
+        Push(f32),
+        Spawn(f32),
+    }
+
+    fn make_world(seed: u64) -> PhysicsWorld {
+        let mut world = PhysicsWorld::new(WorldConfig {
+            seed: Some(seed),
+            ..WorldConfig::side_view()
+        });
+        for x in 1..40 {
+            world.spawn(Entity::new(EntityType::Static, (x as f32, 8.0)));
+        }
+        world.spawn(Entity::new(EntityType::Player, (5.0, 7.0)));
+        world.spawn_body(RigidBody::rectangle(EntityType::Npc, (10.0, 3.0), 2, 2));
+        world
+    }
+
+    fn apply(world: &mut PhysicsWorld, input: &Input) {
+        match *input {
+            Input::Idle => {}
+            Input::Push(force) => {
+                let player = world
+                    .entities
+                    .iter_mut()
+                    .find(|entity| entity.id == EntityType::Player)
+                    .unwrap();
+                player.apply_force(EuclidianVector::new(force, 0.0));
+            }
+            Input::Spawn(x) => {
+                let mut ball = Entity::new(EntityType::Npc, (x, 1.0));
+                ball.hitbox = Hitbox::Circle;
+                world.spawn(ball);
+            }
+        }
+    }
+
+    fn inputs() -> Vec<Input> {
+        (0..300)
+            .map(|tick| match tick % 50 {
+                0 => Input::Spawn(5.0 + (tick / 50) as f32 * 1.3),
+                10..=20 => Input::Push(300.0),
+                30..=35 => Input::Push(-300.0),
+                _ => Input::Idle,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_seeded_ids_repeat() {
+        let first: Vec<_> = SeededIds::new(7).take(100).collect();
+        let again: Vec<_> = SeededIds::new(7).take(100).collect();
+        let other: Vec<_> = SeededIds::new(8).take(100).collect();
+        assert_eq!(first, again);
+        assert_ne!(first, other);
+
+        let mut unique = first.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), first.len());
+    }
+
+    #[test]
+    fn test_spawned_entities_are_given_seeded_ids() {
+        let (a, b) = (make_world(1), make_world(1));
+        let uuids = |world: &PhysicsWorld| -> Vec<_> {
+            world
+                .entities
+                .iter()
+                .map(|entity| (entity.uuid, entity.body))
+                .collect()
+        };
+        assert_eq!(uuids(&a), uuids(&b));
+        assert_eq!(a.checksum(), b.checksum());
+
+        // the parts of a compound body all belong to its seeded uuid
+        let body = a.entities.last().unwrap().body.unwrap();
+        assert_eq!(
+            a.entities.iter().filter(|e| e.body == Some(body)).count(),
+            4
+        );
+
+        assert_ne!(make_world(2).checksum(), a.checksum());
+    }
+
+    #[test]
+    fn test_replays_match_their_recording() {
+        let mut world = make_world(42);
+        let mut recording = Recording::new();
+        for input in inputs() {
+            recording.record(&mut world, input, apply);
+        }
+        assert_eq!(recording.ticks.len(), 300);
+
+        // the world actually changed along the way
+        let checksums: Vec<u64> = recording.ticks.iter().map(|(_, sum)| *sum).collect();
+        assert_ne!(checksums[0], checksums[299]);
+        assert_eq!(checksums[299], world.checksum());
+
+        assert_eq!(recording.replay(&mut make_world(42), apply), Ok(()));
+    }
+
+    #[test]
+    fn test_checksums_ignore_uuids() {
+        // entities pushed straight into a world are given random uuids
+        let pushed = || -> Vec<Entity> {
+            let mut entities: Vec<Entity> = (1..10)
+                .map(|x| Entity::new(EntityType::Static, (x as f32, 8.0)))
+                .collect();
+            entities.extend(RigidBody::rectangle(EntityType::Npc, (4.0, 3.0), 2, 2).parts);
+            let body = Some(Uuid::new_v4());
+            for part in entities.iter_mut().skip(9) {
+                part.body = body;
+            }
+            entities
+        };
+        let (a, b) = (pushed(), pushed());
+        assert_ne!(a[0].uuid, b[0].uuid);
+        assert_eq!(checksum(&a), checksum(&b));
+
+        // but which entities make up a body still counts
+        let mut split = pushed();
+        split[9].body = None;
+        assert_ne!(checksum(&split), checksum(&a));
+    }
+
+    #[test]
+    fn test_checksums_include_particles() {
+        let emitting = |seed: u64| {
+            let mut world = make_world(seed);
+            world.particles.add_emitter(Emitter {
+                pos: (20.0, 4.0),
+                rate: 100.0,
+                ..Emitter::default()
+            });
+            world
+        };
+        let (mut a, mut b) = (emitting(5), emitting(5));
+        for _ in 0..20 {
+            a.step();
+            b.step();
+            assert_eq!(a.checksum(), b.checksum());
+        }
+        assert!(!a.particles.particles().is_empty());
+
+        // particles emitted differently tell the worlds apart, even with entities alike
+        let mut other = emitting(5);
+        other.particles = ParticleSystem::new(6);
+        other.particles.add_emitter(a.particles.emitters[0].clone());
+        for _ in 0..20 {
+            other.step();
+        }
+        assert_eq!(checksum(&other.entities), checksum(&a.entities));
+        assert_ne!(other.checksum(), a.checksum());
+    }
+
+    #[test]
+    fn test_checksums_include_platforms_paths_and_joints() {
+        let base = checksum(&[Entity::new(EntityType::Static, (4.0, 8.0))]);
+        let mut platform = Entity::new(EntityType::Static, (4.0, 8.0));
+        platform.one_way = Some(EuclidianVector::new(0.0, -1.0));
+        assert_ne!(checksum(&[platform]), base);
+
+        let path = Path::new(vec![(1.0, 1.0), (5.0, 1.0)], 2.0);
+        let base = checksum(&[Entity::kinematic(path.clone())]);
+        let changes: [fn(&mut Path); 4] = [
+            |path| path.waypoints[1].0 = 6.0,
+            |path| path.speed = 3.0,
+            |path| path.mode = PathMode::Loop,
+            |path| path.easing = Easing::Smooth,
+        ];
+        for change in changes {
+            let mut changed = path.clone();
+            change(&mut changed);
+            assert_ne!(checksum(&[Entity::kinematic(changed)]), base);
+        }
+
+        let mut world = make_world(3);
+        let base = world.checksum();
+        let (a, b) = (&world.entities[39], &world.entities[40]);
+        let joint = Joint::distance(Anchor::centroid(a), Anchor::centroid(b), 3.0);
+        world.joints.push(joint);
+        let joined = world.checksum();
+        assert_ne!(joined, base);
+        world.joints[0].kind = JointKind::Rope { length: 3.0 };
+        assert_ne!(world.checksum(), joined);
+    }
+
+    #[test]
+    fn test_replays_report_where_they_diverge() {
+        let mut world = make_world(42);
+        let mut recording = Recording::new();
+        for input in inputs() {
+            recording.record(&mut world, input, apply);
+        }
+
+        // a different seed differs from the very start
+        let divergence = recording.replay(&mut make_world(43), apply).unwrap_err();
+        assert_eq!(divergence.tick, 0);
+
+        // as does a different input, from the tick it was applied
+        let mut tampered = recording.clone();
+        tampered.ticks[120].0 = Input::Push(250.0);
+        let divergence = tampered.replay(&mut make_world(42), apply).unwrap_err();
+        assert_eq!(divergence.tick, 120);
+        assert_eq!(divergence.expected, recording.ticks[120].1);
+    }
+}
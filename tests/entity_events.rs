@@ -0,0 +1,146 @@
+#[cfg(test)]
+mod tests_contact_events {
+    use ascii_arcade::entity::events::{ContactEvent, ContactPhase};
+    use ascii_arcade::entity::{collision, vector::EuclidianVector, *};
+
+    fn phases(events: &[ContactEvent]) -> Vec<ContactPhase> {
+        events.iter().map(|event| event.phase).collect()
+    }
+
+    #[test]
+    fn test_sensors_report_overlaps_without_pushing() {
+        let mut world = PhysicsWorld::default();
+        let mut zone = Entity::new(EntityType::Static, (10.0, 5.0));
+        zone.sensor = true;
+        zone.hitbox = Hitbox::Aabb {
+            half_width: 2.0,
+            half_height: 2.0,
+        };
+        let mut ball = Entity::new(EntityType::Npc, (5.0, 5.0));
+        ball.hitbox = Hitbox::Circle;
+        ball.vel = EuclidianVector::new(10.0, 0.0);
+        world.entities = vec![zone, ball];
+
+        let mut seen: Vec<ContactPhase> = Vec::new();
+        for _ in 0..100 {
+            world.step();
+            for event in world.drain_events() {
+                assert_eq!(
+                    event.other(world.entities[0].uuid),
+                    Some(world.entities[1].uuid)
+                );
+                if seen.last() != Some(&event.phase) {
+                    seen.push(event.phase);
+                }
+            }
+        }
+
+        // the ball passed straight through, without slowing down
+        assert_eq!(
+            seen,
+            [ContactPhase::Begin, ContactPhase::Stay, ContactPhase::End]
+        );
+        assert_eq!(world.entities[1].vel.x, 10.0);
+        assert!(world.entities[1].pos.0 > 14.0);
+    }
+
+    #[test]
+    fn test_collisions_are_reported_as_they_happen() {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        let floor = Entity::new(EntityType::Static, (10.0, 8.0));
+        let crate_ = Entity::new(EntityType::Npc, (10.0, 6.98));
+        let floor_id = floor.uuid;
+        let crate_id = crate_.uuid;
+        world.entities = vec![floor, crate_];
+
+        // dropped onto the floor, too gently to bounce
+        let mut events = Vec::new();
+        while events.is_empty() {
+            world.step();
+            events = world.drain_events();
+        }
+        assert_eq!(phases(&events), [ContactPhase::Begin]);
+        assert!(events[0].involves(floor_id) && events[0].involves(crate_id));
+        assert_eq!(events[0].contact.manifold.normal.y.abs(), 1.0);
+
+        // resting on the floor, including once asleep
+        for _ in 0..200 {
+            world.step();
+            assert_eq!(phases(&world.drain_events()), [ContactPhase::Stay]);
+        }
+        assert!(world.entities[1].asleep);
+
+        // knocked off the floor
+        world.entities[1].vel = EuclidianVector::new(0.0, -10.0);
+        world.step();
+        assert_eq!(phases(&world.drain_events()), [ContactPhase::End]);
+        world.step();
+        assert!(world.drain_events().is_empty());
+    }
+
+    #[test]
+    fn test_overlapping_statics_never_touch() {
+        // a platform placed over the cells of a floor, and a kinematic passing through them
+        let mut platform = Entity::new(EntityType::Static, (10.0, 8.0));
+        platform.hitbox = Hitbox::Aabb {
+            half_width: 2.5,
+            half_height: 0.5,
+        };
+        let mut entities: Vec<Entity> = (8..13)
+            .map(|x| Entity::new(EntityType::Static, (x as f32, 8.0)))
+            .collect();
+        entities.push(platform);
+        entities.push(Entity::kinematic(Path::new(
+            vec![(5.0, 8.0), (15.0, 8.0)],
+            5.0,
+        )));
+        assert!(collision::detect(&entities).is_empty());
+
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        world.entities = entities;
+        for _ in 0..100 {
+            world.step();
+            assert!(world.drain_events().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_undrained_events_are_discarded() {
+        let mut world = PhysicsWorld::default();
+        let mut left = Entity::new(EntityType::Npc, (10.0, 5.0));
+        left.vel.x = -5.0;
+        let mut right = Entity::new(EntityType::Npc, (10.8, 5.0));
+        right.vel.x = 5.0;
+        world.entities = vec![left, right];
+
+        // drifting apart, only touching during the first step
+        world.step();
+        world.step();
+        let events = world.drain_events();
+        assert_eq!(phases(&events), [ContactPhase::End]);
+        assert!(world.drain_events().is_empty());
+    }
+
+    #[test]
+    fn test_removed_entities_stop_touching() {
+        let mut world = PhysicsWorld::default();
+        let mut pickup = Entity::new(EntityType::Npc, (10.5, 5.0));
+        pickup.sensor = true;
+        world.entities = vec![Entity::new(EntityType::Player, (10.0, 5.0)), pickup];
+
+        world.step();
+        let picked: Vec<_> = world
+            .drain_events()
+            .iter()
+            .filter(|event| event.phase == ContactPhase::Begin)
+            .filter_map(|event| event.other(world.entities[0].uuid))
+            .collect();
+        assert_eq!(picked, [world.entities[1].uuid]);
+        world
+            .entities
+            .retain(|entity| !picked.contains(&entity.uuid));
+
+        world.step();
+        assert_eq!(phases(&world.drain_events()), [ContactPhase::End]);
+    }
+}
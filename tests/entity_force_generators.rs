@@ -0,0 +1,170 @@
+mod common;
+
+#[cfg(test)]
+mod tests_force_generators {
+    use crate::common::approx;
+    use ascii_arcade::entity::forces::*;
+    use ascii_arcade::entity::{vector::EuclidianVector, *};
+
+    #[test]
+    fn test_generators_only_apply_to_filtered_entities() {
+        let mut world = PhysicsWorld::default();
+        world.add_generator_filtered(Gravity(EuclidianVector::new(0.0, -5.0)), |entity| {
+            entity.id == EntityType::Npc
+        });
+        world.entities = vec![
+            Entity::new(EntityType::Npc, (10.0, 5.0)),
+            Entity::new(EntityType::Player, (20.0, 5.0)),
+            Entity::new(EntityType::Static, (30.0, 5.0)),
+        ];
+        world.step();
+
+        assert!(approx(world.entities[0].vel.y, -0.05, 1e-6));
+        assert_eq!(world.entities[1].vel.y, 0.0);
+        assert_eq!(world.entities[2].pos, (30.0, 5.0));
+
+        world.clear_generators();
+        world.step();
+        assert!(approx(world.entities[0].vel.y, -0.05, 1e-6));
+    }
+
+    #[test]
+    fn test_drag_limits_falling_speed() {
+        // terminal velocity is reached when drag balances gravity, m * g = k * v (or k * v^2)
+        for drag in [
+            Drag {
+                linear: 9.81,
+                quadratic: 0.0,
+            },
+            Drag {
+                linear: 0.0,
+                quadratic: 9.81,
+            },
+        ] {
+            let mut world = PhysicsWorld::new(WorldConfig::side_view());
+            world.add_generator(drag);
+            world.entities = vec![Entity::new(EntityType::Npc, (10.0, 1.0))];
+            for _ in 0..100 {
+                world.step();
+            }
+            assert!(approx(world.entities[0].vel.y, 1.0, 0.01));
+        }
+    }
+
+    #[test]
+    fn test_wind_carries_entities_along() {
+        let mut world = PhysicsWorld::default();
+        world.add_generator(Wind {
+            vel: EuclidianVector::new(3.0, 0.0),
+            coefficient: 5.0,
+        });
+        world.entities = vec![Entity::new(EntityType::Npc, (5.0, 5.0))];
+        for _ in 0..200 {
+            world.step();
+        }
+        assert!(approx(world.entities[0].vel.x, 3.0, 0.01));
+        assert_eq!(world.entities[0].vel.y, 0.0);
+    }
+
+    #[test]
+    fn test_wind_wakes_asleep_entities() {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        let floor = world.config.bounds.bottom;
+        world.entities = vec![Entity::new(EntityType::Npc, (10.0, floor))];
+        for _ in 0..100 {
+            world.step();
+        }
+        assert!(world.entities[0].asleep);
+
+        world.add_generator(Wind {
+            vel: EuclidianVector::new(-20.0, 0.0),
+            coefficient: 5.0,
+        });
+        for _ in 0..20 {
+            world.step();
+        }
+        assert!(!world.entities[0].asleep);
+        assert!(world.entities[0].pos.0 < 9.0);
+    }
+
+    #[test]
+    fn test_steady_fields_leave_resting_piles_asleep() {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        world.add_generator(Gravity(EuclidianVector::new(0.0, 5.0)));
+        world.entities = (1..40)
+            .map(|x| Entity::new(EntityType::Static, (x as f32, 8.0)))
+            .collect();
+        for y in [7.0, 6.0, 5.0] {
+            world.entities.push(Entity::new(EntityType::Npc, (5.0, y)));
+        }
+        for _ in 0..300 {
+            world.step();
+        }
+        assert!(world.entities[39..].iter().all(|e| e.asleep));
+
+        // the floor holds up the pile against the extra pull, so it stays asleep
+        let before: Vec<(f32, f32)> = world.entities.iter().map(|e| e.pos).collect();
+        for _ in 0..100 {
+            world.step();
+            assert!(world.entities[39..].iter().all(|e| e.asleep));
+        }
+        let after: Vec<(f32, f32)> = world.entities.iter().map(|e| e.pos).collect();
+        assert_eq!(before, after);
+
+        // until the pull changes
+        world.clear_generators();
+        world.step();
+        assert!(world.entities[39..].iter().all(|e| !e.asleep));
+    }
+
+    #[test]
+    fn test_attractors_pull_and_push() {
+        let attractor = |strength: f32| PointAttractor {
+            point: (25.0, 5.0),
+            strength,
+            min_distance: 1.0,
+        };
+        let entity = Entity::new(EntityType::Npc, (20.0, 5.0));
+        let config = WorldConfig::default();
+
+        // inverse-square, towards the point
+        let pull = attractor(50.0).force(&entity, &config);
+        assert_eq!((pull.x, pull.y), (2.0, 0.0));
+        let push = attractor(-50.0).force(&entity, &config);
+        assert_eq!((push.x, push.y), (-2.0, 0.0));
+
+        // capped close to the point
+        let close = Entity::new(EntityType::Npc, (25.0, 5.5));
+        let pull = attractor(50.0).force(&close, &config);
+        assert_eq!((pull.x, pull.y), (0.0, -50.0));
+    }
+
+    #[test]
+    fn test_buoyancy_floats_light_entities() {
+        let pool = |density: f32| Buoyancy {
+            region: Bounds {
+                left: 0.0,
+                top: 5.0,
+                right: 50.0,
+                bottom: 9.0,
+            },
+            density,
+            drag: 5.0,
+        };
+
+        // a crate weighing half as much as the water it'd displace floats half-submerged,
+        // whereas one weighing twice as much sinks to the bottom
+        let mut floats = PhysicsWorld::new(WorldConfig::side_view());
+        floats.add_generator(pool(2.0));
+        let mut sinks = PhysicsWorld::new(WorldConfig::side_view());
+        sinks.add_generator(pool(0.5));
+        for world in [&mut floats, &mut sinks] {
+            world.entities = vec![Entity::new(EntityType::Npc, (10.0, 3.0))];
+            for _ in 0..1000 {
+                world.step();
+            }
+        }
+        assert!(approx(floats.entities[0].pos.1, 5.0, 0.05));
+        assert!(sinks.entities[0].pos.1 > 8.0);
+    }
+}
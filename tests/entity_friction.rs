@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod test_entity_friction {
+    use ascii_arcade::entity::*;
+
+    /// slides an entity along the top of a row of statics for the given number of updates
+    fn slide_across_statics(mut slider: Entity, updates: usize) -> Entity {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        world.entities = (1..40)
+            .map(|x| Entity::new(EntityType::Static, (x as f32, 6.0)))
+            .collect();
+        slider.pos = (5.0, 5.0);
+        world.entities.push(slider);
+
+        for _ in 0..updates {
+            world.step();
+        }
+        world.entities.pop().unwrap()
+    }
+
+    #[test]
+    fn test_kinetic_friction_slows_sliding_entities() {
+        let mut slider = Entity::new(EntityType::Npc, (0.0, 0.0));
+        slider.vel.x = 5.0;
+        let slider = slide_across_statics(slider, 50);
+
+        // decelerating at roughly (kinetic friction * gravity)
+        assert!(slider.vel.x > 0.0);
+        assert!((slider.vel.x - (5.0 - 0.4 * 9.81 * 0.5)).abs() < 0.25);
+    }
+
+    #[test]
+    fn test_seams_between_statics_dont_stop_sliding_entities() {
+        // the slider crosses the seams between statics at all sorts of offsets
+        for i in 0..25 {
+            let mut world = PhysicsWorld::new(WorldConfig::side_view());
+            world.entities = (1..40)
+                .map(|x| Entity::new(EntityType::Static, (x as f32, 6.0)))
+                .collect();
+            let mut slider = Entity::new(EntityType::Npc, (5.0, 5.0));
+            slider.vel.x = 2.0 + i as f32 * 0.25;
+            world.entities.push(slider);
+
+            for _ in 0..150 {
+                world.step();
+                assert!(world.entities[39].vel.x >= 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_static_friction_stops_sliding_entities() {
+        let mut slider = Entity::new(EntityType::Npc, (0.0, 0.0));
+        slider.vel.x = 5.0;
+        let slider = slide_across_statics(slider, 300);
+        assert_eq!(slider.vel.x, 0.0);
+    }
+
+    #[test]
+    fn test_frictionless_entities_keep_sliding() {
+        let mut slider = Entity::new(EntityType::Npc, (0.0, 0.0));
+        (slider.static_friction, slider.kinetic_friction) = (0.0, 0.0);
+        slider.vel.x = 5.0;
+        let slider = slide_across_statics(slider, 300);
+        assert!((slider.vel.x - 5.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_window_floor_applies_friction() {
+        // starts resting on the floor of the world
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        let mut entity = Entity::new(EntityType::Npc, (5.0, world.config.bounds.bottom));
+        entity.vel.x = 5.0;
+        world.entities.push(entity);
+        for _ in 0..50 {
+            world.step();
+        }
+        let entity = &world.entities[0];
+        assert!(entity.grounded);
+        assert!((entity.vel.x - (5.0 - 0.4 * 9.81 * 0.5)).abs() < 0.25);
+    }
+}
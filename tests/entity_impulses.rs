@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod test_entity_impulses {
+    use ascii_arcade::entity::{collision, vector::EuclidianVector, *};
+
+    /// two overlapping entities closing in on one another along the x-axis
+    fn make_head_on_collision(ma: f32, mb: f32, e: f32) -> Vec<Entity> {
+        let mut a = Entity::new(EntityType::Npc, (5.0, 5.0));
+        let mut b = Entity::new(EntityType::Npc, (5.8, 5.0));
+        (a.mass, b.mass) = (ma, mb);
+        (a.restitution, b.restitution) = (e, e);
+        (a.vel.x, b.vel.x) = (4.0, -2.0);
+        vec![a, b]
+    }
+
+    fn momentum(entities: &[Entity]) -> EuclidianVector {
+        entities
+            .iter()
+            .fold(EuclidianVector::new(0.0, 0.0), |p, e| {
+                p + e.vel.clone() * e.mass
+            })
+    }
+
+    #[test]
+    fn test_perfectly_elastic_impulse_conserves_kinetic_energy() {
+        let mut entities = make_head_on_collision(1.0, 3.0, 1.0);
+
+        let contacts = collision::detect(&entities);
+        collision::resolve(&mut entities, &contacts);
+
+        // conserving both momentum (m1*u1 + m2*u2 = m1*v1 + m2*v2)
+        // and kinetic energy (m1*u1^2 + m2*u2^2 = m1*v1^2 + m2*v2^2)
+        assert_eq!(entities[0].vel, EuclidianVector::new(-5.0, 0.0));
+        assert_eq!(entities[1].vel, EuclidianVector::new(1.0, 0.0));
+    }
+
+    #[test]
+    fn test_inelastic_impulse_conserves_momentum() {
+        for (ma, mb) in [(1.0, 1.0), (1.0, 3.0), (5.0, 0.5)] {
+            let mut entities = make_head_on_collision(ma, mb, 0.0);
+            let before = momentum(&entities);
+
+            let contacts = collision::detect(&entities);
+            collision::resolve(&mut entities, &contacts);
+
+            // the entities move off together
+            assert!((entities[0].vel.x - entities[1].vel.x).abs() < 1e-5);
+            let after = momentum(&entities);
+            assert!((before.x - after.x).abs() < 1e-5);
+            assert_eq!(before.y, after.y);
+        }
+    }
+
+    #[test]
+    fn test_statics_are_immovable() {
+        let mut entities = make_head_on_collision(1.0, 1.0, 1.0);
+        entities[1].id = EntityType::Static;
+        entities[1].vel.x = 0.0;
+
+        let contacts = collision::detect(&entities);
+        collision::resolve(&mut entities, &contacts);
+
+        assert_eq!(entities[0].vel.x, -4.0);
+        assert_eq!(entities[1].vel.x, 0.0);
+        assert_eq!(entities[1].pos, (5.8, 5.0));
+        assert!(entities[0].pos.0 < 5.0);
+    }
+
+    #[test]
+    fn test_stack_comes_to_rest_on_a_static() {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        world
+            .entities
+            .push(Entity::new(EntityType::Static, (5.0, 8.0)));
+        for y in [7.0, 6.0, 5.0] {
+            world.entities.push(Entity::new(EntityType::Npc, (5.0, y)));
+        }
+
+        for _ in 0..500 {
+            world.step();
+        }
+
+        for (entity, y) in world.entities[1..].iter().zip([7.0, 6.0, 5.0]) {
+            assert!(entity.grounded);
+            assert!(entity.vel.magnitude() < 0.5);
+            assert!((entity.pos.1 - y).abs() < 0.1);
+        }
+    }
+
+    #[test]
+    fn test_tall_stacks_are_held_still() {
+        for height in [4, 6] {
+            let mut world = PhysicsWorld::new(WorldConfig::side_view());
+            world.config.sleep_ticks = None;
+            world
+                .entities
+                .push(Entity::new(EntityType::Static, (5.0, 8.0)));
+            for i in 1..=height {
+                let y = 8.0 - i as f32;
+                world.entities.push(Entity::new(EntityType::Npc, (5.0, y)));
+            }
+
+            for _ in 0..300 {
+                world.step();
+            }
+            let before: Vec<(f32, f32)> = world.entities.iter().map(|e| e.pos).collect();
+            for _ in 0..100 {
+                world.step();
+            }
+
+            // no longer moving at all, nor slowly sinking into one another
+            for (entity, (x, y)) in world.entities[1..].iter().zip(&before[1..]) {
+                assert!(entity.vel.magnitude() < 1e-3);
+                assert!((entity.pos.0 - x).abs() < 1e-4);
+                assert!((entity.pos.1 - y).abs() < 1e-4);
+            }
+        }
+    }
+}
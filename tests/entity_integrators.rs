@@ -0,0 +1,166 @@
+#[cfg(test)]
+mod tests_integrators {
+    use ascii_arcade::entity::{vector::EuclidianVector, *};
+
+    const ALL: [Integrator; 4] = [
+        Integrator::Explicit,
+        Integrator::SemiImplicitEuler,
+        Integrator::VelocityVerlet,
+        Integrator::RungeKutta4,
+    ];
+
+    /// returns the largest relative change in energy whilst integrating some motion.
+    fn energy_drift<A, E>(
+        integrator: Integrator,
+        mut pos: (f32, f32),
+        mut vel: EuclidianVector,
+        steps: usize,
+        acceleration: A,
+        energy: E,
+    ) -> f32
+    where
+        A: Fn((f32, f32), &EuclidianVector) -> EuclidianVector,
+        E: Fn((f32, f32), &EuclidianVector) -> f32,
+    {
+        let initial = energy(pos, &vel);
+        let mut drift: f32 = 0.0;
+        for _ in 0..steps {
+            (pos, vel) = integrator.integrate(pos, &vel, 0.01, &acceleration);
+            drift = drift.max(((energy(pos, &vel) - initial) / initial).abs());
+        }
+        drift
+    }
+
+    /// a pendulum making small swings, so it moves like a spring (simple harmonic motion).
+    fn pendulum_drift(integrator: Integrator) -> f32 {
+        let w2 = 9.81 / 2.0;
+        energy_drift(
+            integrator,
+            (0.3, 0.0),
+            EuclidianVector::new(0.0, 0.0),
+            3_000,
+            |p, _| EuclidianVector::new(-w2 * p.0, 0.0),
+            |p, v| 0.5 * v.dot(v) + 0.5 * w2 * p.0 * p.0,
+        )
+    }
+
+    /// a body in a circular orbit around a point mass, for ten orbits.
+    fn orbit_drift(integrator: Integrator) -> f32 {
+        let distance = |p: (f32, f32)| (p.0 * p.0 + p.1 * p.1).sqrt();
+        energy_drift(
+            integrator,
+            (1.0, 0.0),
+            EuclidianVector::new(0.0, 1.0),
+            6_283,
+            |p, _| {
+                let r = distance(p);
+                EuclidianVector::new(-p.0 / (r * r * r), -p.1 / (r * r * r))
+            },
+            |p, v| 0.5 * v.dot(v) - 1.0 / distance(p),
+        )
+    }
+
+    #[test]
+    fn test_pendulum_energy_drift() {
+        let drift: Vec<f32> = ALL.into_iter().map(pendulum_drift).collect();
+
+        // each integrator is more accurate than the last
+        assert!(drift.windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(drift[1] < 0.05);
+        assert!(drift[2] < 1e-3);
+        assert!(drift[3] < 1e-4);
+    }
+
+    #[test]
+    fn test_orbit_energy_drift() {
+        let drift: Vec<f32> = ALL.into_iter().map(orbit_drift).collect();
+
+        // the explicit scheme spirals out of orbit, whilst the others stay in orbit
+        assert!(drift[0] > 1.0);
+        assert!(drift[1] < 1e-3);
+        assert!(drift[2] < 1e-4);
+        assert!(drift[3] < 1e-4);
+    }
+
+    #[test]
+    fn test_integrator_is_selected_per_world() {
+        // falling for a second under gravity, which is exact for some integrators
+        let fall = |integrator: Integrator| {
+            let mut world = PhysicsWorld::new(WorldConfig {
+                integrator,
+                gravity: EuclidianVector::new(0.0, 2.0),
+                ..Default::default()
+            });
+            world
+                .entities
+                .push(Entity::new(EntityType::Npc, (5.0, 1.0)));
+            for _ in 0..100 {
+                world.step();
+            }
+            world.entities[0].pos.1 - 1.0
+        };
+        assert!((fall(Integrator::VelocityVerlet) - 1.0).abs() < 1e-4);
+        assert!((fall(Integrator::RungeKutta4) - 1.0).abs() < 1e-4);
+        assert!((fall(Integrator::SemiImplicitEuler) - 1.01).abs() < 1e-4);
+        assert!((fall(Integrator::Explicit) - 1.02).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_target_pos_accounts_for_the_integrator() {
+        for integrator in ALL {
+            let config = WorldConfig {
+                integrator,
+                ..Default::default()
+            };
+            let mut entity = Entity::new(EntityType::Npc, (5.0, 5.0));
+            entity.vel.x = 3.0;
+            let force = entity.target_pos(5.5, 4.0, &config);
+            entity.apply_force(force);
+            entity.update(&config);
+            assert!((entity.pos.0 - 5.5).abs() < 1e-4);
+            assert!((entity.pos.1 - 4.0).abs() < 1e-4);
+        }
+    }
+
+    /// a weight on a spring (without any damping) bouncing back and forth in a world.
+    fn world_spring_drift(integrator: Integrator) -> f32 {
+        let (stiffness, rest_length) = (20.0, 3.0);
+        let mut world = PhysicsWorld::new(WorldConfig {
+            integrator,
+            sleep_ticks: None,
+            ..Default::default()
+        });
+        let weight = Entity::new(EntityType::Npc, (30.0, 5.0));
+        world.joints.push(Joint::spring(
+            Anchor::Point((25.0, 5.0)),
+            Anchor::centroid(&weight),
+            rest_length,
+            stiffness,
+            0.0,
+        ));
+        world.entities.push(weight);
+
+        let energy = |weight: &Entity| {
+            let stretch = weight.pos.0 - 25.0 - rest_length;
+            0.5 * weight.mass * weight.vel.dot(&weight.vel) + 0.5 * stiffness * stretch * stretch
+        };
+        let initial = energy(&world.entities[0]);
+        let mut drift: f32 = 0.0;
+        for _ in 0..1_000 {
+            world.step();
+            drift = drift.max(((energy(&world.entities[0]) - initial) / initial).abs());
+        }
+        drift
+    }
+
+    #[test]
+    fn test_world_spring_energy_drift() {
+        let drift: Vec<f32> = ALL.into_iter().map(world_spring_drift).collect();
+
+        // springs are worked out again at each stage, so each integrator is more accurate than the last
+        assert!(drift.windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(drift[1] < 0.05);
+        assert!(drift[2] < 1e-3);
+        assert!(drift[3] < 1e-4);
+    }
+}
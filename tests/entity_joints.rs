@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod tests_joints {
+    use ascii_arcade::entity::{sleep, vector::EuclidianVector, *};
+
+    fn ball(pos: (f32, f32)) -> Entity {
+        let mut ball = Entity::new(EntityType::Npc, pos);
+        ball.hitbox = Hitbox::Circle;
+        ball
+    }
+
+    fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+        EuclidianVector::from(a, b).magnitude()
+    }
+
+    #[test]
+    fn test_pendulums_swing_at_a_fixed_length() {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        world.config.sleep_ticks = None;
+        let bob = ball((13.0, 2.0));
+        world.joints = vec![Joint::distance(
+            Anchor::centroid(&bob),
+            Anchor::Point((10.0, 2.0)),
+            3.0,
+        )];
+        world.entities = vec![bob];
+
+        let mut furthest_left = f32::INFINITY;
+        for _ in 0..300 {
+            world.step();
+            let pos = world.entities[0].pos;
+            assert!((distance(pos, (10.0, 2.0)) - 3.0).abs() < 0.01);
+            furthest_left = furthest_left.min(pos.0);
+        }
+        // swung down and most of the way back up the other side
+        assert!(furthest_left < 7.5);
+    }
+
+    #[test]
+    fn test_ropes_only_stop_entities_moving_apart() {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        let weight = Entity::new(EntityType::Npc, (10.0, 2.0));
+        world.joints = vec![Joint::rope(
+            Anchor::Point((10.0, 1.0)),
+            Anchor::centroid(&weight),
+            4.0,
+        )];
+        world.entities = vec![weight];
+
+        // falls freely whilst the rope is slack
+        for _ in 0..50 {
+            world.step();
+        }
+        assert!(world.entities[0].pos.1 > 2.5 && world.entities[0].pos.1 < 5.0);
+
+        // then hangs from the end of the rope
+        for _ in 0..100 {
+            world.step();
+        }
+        assert!((world.entities[0].pos.1 - 5.0).abs() < 0.01);
+        assert!(world.entities[0].vel.y.abs() < 0.1);
+
+        // but can be thrown back up towards the anchor
+        world.entities[0].vel.y = -5.0;
+        world.step();
+        assert!(world.entities[0].pos.1 < 5.0);
+    }
+
+    #[test]
+    fn test_revolute_joints_pin_entities_in_place() {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        world.config.sleep_ticks = None;
+        let wheel = ball((10.0, 5.0));
+        world.joints = vec![Joint::revolute(&wheel, (9.5, 5.0))];
+        world.entities = vec![wheel];
+
+        // the wheel hangs off the pin by its edge, so swings down beneath it, turning as it goes
+        for _ in 0..20 {
+            world.step();
+        }
+        let wheel = &world.entities[0];
+        assert!(wheel.pos.1 > 5.0);
+        assert!(distance(wheel.pos, (9.5, 5.0)) < 0.51);
+        assert!(wheel.angular_vel > 0.0);
+    }
+
+    #[test]
+    fn test_springs_settle_at_their_rest_length() {
+        let mut world = PhysicsWorld::default();
+        let a = Entity::new(EntityType::Npc, (10.0, 5.0));
+        let b = Entity::new(EntityType::Npc, (16.0, 5.0));
+        world.joints = vec![Joint::spring(
+            Anchor::centroid(&a),
+            Anchor::centroid(&b),
+            3.0,
+            50.0,
+            5.0,
+        )];
+        world.entities = vec![a, b];
+
+        let mut shortest = f32::INFINITY;
+        for _ in 0..500 {
+            world.step();
+            shortest = shortest.min(distance(world.entities[0].pos, world.entities[1].pos));
+        }
+        let (a, b) = (&world.entities[0], &world.entities[1]);
+        assert!((distance(a.pos, b.pos) - 3.0).abs() < 0.05);
+        // pulled together evenly, overshooting a little before the damping settles them
+        assert!((a.pos.0 - 11.5).abs() < 0.05 && (b.pos.0 - 14.5).abs() < 0.05);
+        assert!(shortest < 3.0);
+    }
+
+    #[test]
+    fn test_chains_hang_together() {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        let links: Vec<Entity> = (1..=5)
+            .map(|i| Entity::new(EntityType::Npc, (10.0 + 1.5 * i as f32, 1.0)))
+            .collect();
+        world.joints.push(Joint::rope(
+            Anchor::Point((10.0, 1.0)),
+            Anchor::centroid(&links[0]),
+            1.5,
+        ));
+        for pair in links.windows(2) {
+            world.joints.push(Joint::rope(
+                Anchor::centroid(&pair[0]),
+                Anchor::centroid(&pair[1]),
+                1.5,
+            ));
+        }
+        world.entities = links;
+
+        // jointed entities make up a single island, so sleep together
+        assert_eq!(
+            sleep::islands(&world.entities, &world.joints),
+            vec![vec![0, 1, 2, 3, 4]]
+        );
+
+        // swinging from side to side, without the links coming apart
+        let mut lowest: f32 = 0.0;
+        for _ in 0..500 {
+            world.step();
+            let mut previous = (10.0, 1.0);
+            for link in world.entities.iter() {
+                assert!(distance(previous, link.pos) < 1.55);
+                previous = link.pos;
+            }
+            lowest = lowest.max(previous.1);
+        }
+        assert!(lowest > 7.0);
+
+        // removed links are simply left out
+        world.entities.remove(2);
+        world.step();
+    }
+}
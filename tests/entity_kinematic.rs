@@ -0,0 +1,177 @@
+mod common;
+
+#[cfg(test)]
+mod tests_kinematic {
+    use crate::common::{approx_point, open_side_view};
+    use ascii_arcade::entity::{vector::EuclidianVector, *};
+
+    fn platform(path: Path) -> Entity {
+        let mut platform = Entity::kinematic(path);
+        platform.hitbox = Hitbox::Aabb {
+            half_width: 3.0,
+            half_height: 0.5,
+        };
+        platform
+    }
+
+    #[test]
+    fn test_paths_travel_between_waypoints() {
+        let mut path = Path::new(vec![(0.0, 0.0), (10.0, 0.0), (10.0, 5.0)], 5.0);
+        for (elapsed, expected) in [
+            (0.0, (0.0, 0.0)),
+            (1.0, (5.0, 0.0)),
+            (2.5, (10.0, 2.5)),
+            (3.0, (10.0, 5.0)),
+            // paths followed once stop at the last waypoint
+            (10.0, (10.0, 5.0)),
+        ] {
+            path.elapsed = elapsed;
+            assert!(approx_point(path.position().unwrap(), expected, 1e-4));
+        }
+
+        path.mode = PathMode::PingPong;
+        for (elapsed, expected) in [(4.0, (10.0, 0.0)), (5.0, (5.0, 0.0)), (7.0, (5.0, 0.0))] {
+            path.elapsed = elapsed;
+            assert!(approx_point(path.position().unwrap(), expected, 1e-4));
+        }
+
+        // looping back from (10, 5) to the start is a leg of length sqrt(125)
+        path.mode = PathMode::Loop;
+        let lap = 3.0 + 125f32.sqrt() / 5.0;
+        for (elapsed, expected) in [
+            (3.0 + 125f32.sqrt() / 10.0, (5.0, 2.5)),
+            (lap + 1.0, (5.0, 0.0)),
+        ] {
+            path.elapsed = elapsed;
+            assert!(approx_point(path.position().unwrap(), expected, 1e-4));
+        }
+
+        assert_eq!(Path::new(Vec::new(), 1.0).position(), None);
+        assert_eq!(
+            Path::new(vec![(1.0, 2.0)], 1.0).position(),
+            Some((1.0, 2.0))
+        );
+    }
+
+    #[test]
+    fn test_eased_paths_slow_into_each_waypoint() {
+        let mut path = Path {
+            easing: Easing::Smooth,
+            ..Path::new(vec![(0.0, 0.0), (10.0, 0.0)], 10.0)
+        };
+        // a quarter of the way through the leg in time, but less in distance
+        path.elapsed = 0.25;
+        assert!(approx_point(path.position().unwrap(), (1.5625, 0.0), 1e-4));
+        path.elapsed = 0.5;
+        assert!(approx_point(path.position().unwrap(), (5.0, 0.0), 1e-4));
+        path.elapsed = 1.0;
+        assert!(approx_point(path.position().unwrap(), (10.0, 0.0), 1e-4));
+    }
+
+    #[test]
+    fn test_kinematics_follow_their_path_ignoring_forces() {
+        let mut world = PhysicsWorld::new(open_side_view());
+        world.add_generator(forces::Wind {
+            vel: EuclidianVector::new(-10.0, 0.0),
+            coefficient: 5.0,
+        });
+        let mut mover = platform(Path::new(vec![(10.0, 50.0), (30.0, 50.0)], 5.0));
+        mover.apply_force(EuclidianVector::new(0.0, -100.0));
+        world.entities = vec![mover];
+
+        for _ in 0..100 {
+            world.step();
+        }
+        let mover = &world.entities[0];
+        assert!(approx_point(mover.pos, (15.0, 50.0), 1e-3));
+        assert!(approx_point((mover.vel.x, mover.vel.y), (5.0, 0.0), 1e-2));
+
+        for _ in 0..400 {
+            world.step();
+        }
+        assert_eq!(world.entities[0].pos, (30.0, 50.0));
+        assert_eq!(world.entities[0].vel.magnitude(), 0.0);
+    }
+
+    #[test]
+    fn test_kinematics_push_entities_in_their_way() {
+        let mut world = PhysicsWorld::new(open_side_view());
+        let mut sweeper = Entity::kinematic(Path::new(vec![(10.0, 50.0), (40.0, 50.0)], 5.0));
+        sweeper.hitbox = Hitbox::Aabb {
+            half_width: 0.5,
+            half_height: 3.0,
+        };
+        let crate_ = Entity::new(EntityType::Npc, (20.0, 50.0));
+        world.config.gravity = EuclidianVector::new(0.0, 0.0);
+        world.entities = vec![sweeper, crate_];
+
+        for _ in 0..400 {
+            world.step();
+        }
+        // the crate was shoved along ahead of the sweeper, which kept to its path
+        let (sweeper, crate_) = (&world.entities[0], &world.entities[1]);
+        assert!(approx_point(sweeper.pos, (30.0, 50.0), 1e-3));
+        assert!(crate_.pos.0 >= 30.9);
+        assert!(approx_point((crate_.pos.1, 0.0), (50.0, 0.0), 1e-3));
+    }
+
+    #[test]
+    fn test_kinematics_carry_entities_standing_on_them() {
+        // a lift carrying a crate upwards, then a platform carrying it sideways
+        let mut world = PhysicsWorld::new(open_side_view());
+        let lift = platform(Path {
+            easing: Easing::Smooth,
+            ..Path::new(vec![(50.0, 80.0), (50.0, 60.0), (70.0, 60.0)], 4.0)
+        });
+        let mut crate_ = Entity::new(EntityType::Npc, (50.0, 79.0));
+        crate_.restitution = 0.0;
+        world.entities = vec![lift, crate_];
+
+        for _ in 0..500 {
+            world.step();
+            // the crate stays on top of the lift the whole way
+            let (lift, crate_) = (&world.entities[0], &world.entities[1]);
+            assert!((crate_.pos.1 - (lift.pos.1 - 1.0)).abs() < 0.1);
+        }
+        assert!(world.entities[1].grounded);
+        assert!((world.entities[1].pos.1 - 59.0).abs() < 0.1);
+
+        for _ in 0..500 {
+            world.step();
+        }
+        let (lift, crate_) = (&world.entities[0], &world.entities[1]);
+        assert_eq!(lift.pos, (70.0, 60.0));
+        assert!((crate_.pos.0 - 70.0).abs() < 3.0);
+        assert!((crate_.pos.1 - 59.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_kinematics_are_not_pushed_by_what_lands_on_them() {
+        let mut world = PhysicsWorld::new(open_side_view());
+        let path = Path {
+            mode: PathMode::PingPong,
+            ..Path::new(vec![(40.0, 50.0), (60.0, 50.0)], 2.0)
+        };
+        world.entities = vec![platform(path.clone())];
+        let mut heavy = Entity::new(EntityType::Npc, (41.0, 40.0));
+        heavy.mass = 100.0;
+        heavy.restitution = 0.0;
+        world.entities.push(heavy);
+
+        let mut expected = path;
+        for _ in 0..300 {
+            world.step();
+            expected.elapsed += world.config.time_step;
+            assert!(approx_point(
+                world.entities[0].pos,
+                expected.position().unwrap(),
+                1e-4
+            ));
+        }
+        // riding along on top of the platform
+        let (platform, heavy) = (&world.entities[0], &world.entities[1]);
+        assert!(heavy.grounded);
+        assert!((heavy.pos.1 - 49.0).abs() < 0.1);
+        assert!((heavy.vel.x - platform.vel.x).abs() < 0.1);
+    }
+}
@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod tests_layers {
+    use ascii_arcade::entity::{collision, vector::EuclidianVector, *};
+
+    const TERRAIN: u32 = 1 << 1;
+    const PLAYER: u32 = 1 << 2;
+    const DEBRIS: u32 = 1 << 3;
+
+    #[test]
+    fn test_entities_collide_by_default() {
+        let a = Entity::new(EntityType::Npc, (5.0, 5.0));
+        let b = Entity::new(EntityType::Npc, (5.5, 5.0));
+        assert!(a.collides_with(&b));
+        assert_eq!(collision::detect(&[a, b]).len(), 1);
+    }
+
+    #[test]
+    fn test_both_masks_must_allow_a_collision() {
+        let mut debris = Entity::new(EntityType::Npc, (5.0, 5.0));
+        debris.category = DEBRIS;
+        debris.mask = TERRAIN;
+        let mut player = Entity::new(EntityType::Player, (5.5, 5.0));
+        player.category = PLAYER;
+        let mut wall = Entity::new(EntityType::Static, (5.2, 5.0));
+        wall.category = TERRAIN;
+
+        // the player collides with everything, but the debris only collides with terrain
+        assert!(!player.collides_with(&debris) && !debris.collides_with(&player));
+        assert!(debris.collides_with(&wall) && player.collides_with(&wall));
+
+        let entities = [debris, player, wall];
+        let contacts = collision::detect(&entities);
+        assert!(contacts.iter().all(|contact| {
+            let pair = [contact.a, contact.b];
+            !(pair.contains(&entities[0].uuid) && pair.contains(&entities[1].uuid))
+        }));
+        assert_eq!(contacts.len(), 2);
+    }
+
+    #[test]
+    fn test_masked_entities_pass_through_one_another() {
+        let mut world = PhysicsWorld::default();
+        let mut player = Entity::new(EntityType::Player, (10.0, 5.0));
+        player.category = PLAYER;
+        let mut shot = Entity::new(EntityType::Npc, (10.0, 5.0));
+        shot.mask = LAYER_ALL & !PLAYER;
+        shot.vel = EuclidianVector::new(10.0, 0.0);
+        world.entities = vec![player, shot];
+
+        // the player's own shot leaves without pushing the player
+        for _ in 0..50 {
+            world.step();
+        }
+        assert_eq!(world.entities[0].pos, (10.0, 5.0));
+        assert!((world.entities[1].pos.0 - 15.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_bullets_only_stop_at_walls_they_collide_with() {
+        let mut world = PhysicsWorld::new(WorldConfig {
+            max_vel: 1000.0,
+            ..Default::default()
+        });
+        let wall = |x: f32, category: u32| {
+            let mut wall = Entity::new(EntityType::Static, (x, 5.0));
+            wall.category = category;
+            wall
+        };
+        let mut bullet = Entity::new(EntityType::Npc, (2.0, 5.0));
+        bullet.bullet = true;
+        bullet.mask = TERRAIN;
+        bullet.vel = EuclidianVector::new(3000.0, 0.0);
+        world.entities = vec![wall(10.0, LAYER_DEFAULT), wall(30.0, TERRAIN), bullet];
+
+        world.step();
+        assert_eq!(world.entities[2].pos.0, 29.0);
+    }
+}
@@ -0,0 +1,152 @@
+#[cfg(test)]
+mod tests_lifecycle {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use ascii_arcade::entity::{collision, vector::EuclidianVector, *};
+    use uuid::Uuid;
+
+    /// returns a list that hooks can push the uuids of entities onto.
+    fn log() -> Rc<RefCell<Vec<Uuid>>> {
+        Rc::new(RefCell::new(Vec::new()))
+    }
+
+    #[test]
+    fn test_entities_expire_after_their_lifetime() {
+        let mut world = PhysicsWorld::default();
+        let despawned = log();
+        let hook_log = despawned.clone();
+        world.on_despawn(move |entity| hook_log.borrow_mut().push(entity.uuid));
+
+        let mut spark = Entity::new(EntityType::Npc, (5.0, 5.0));
+        spark.ttl = Some(0.5);
+        let spark = world.spawn(spark);
+        let rock = world.spawn(Entity::new(EntityType::Npc, (10.0, 5.0)));
+
+        // the lifetime is counted down in time steps (of 0.01s)
+        for _ in 0..49 {
+            world.step();
+        }
+        assert_eq!(world.entities.len(), 2);
+        world.step();
+        assert_eq!(world.entities.len(), 1);
+        assert_eq!(world.entities[0].uuid, rock);
+        assert_eq!(*despawned.borrow(), vec![spark]);
+    }
+
+    #[test]
+    fn test_despawns_are_queued_until_after_the_step() {
+        let mut world = PhysicsWorld::default();
+        let spawned = log();
+        let hook_log = spawned.clone();
+        world.on_spawn(move |entity| hook_log.borrow_mut().push(entity.uuid));
+
+        let coin = world.spawn(Entity::new(EntityType::Npc, (5.0, 5.0)));
+        let body = world.spawn_body(RigidBody::rectangle(EntityType::Npc, (10.0, 5.0), 2, 2));
+        assert_eq!(spawned.borrow().len(), 5);
+        assert_eq!(spawned.borrow()[0], coin);
+
+        // queued entities are still there until the world steps
+        world.despawn(coin);
+        world.despawn(body);
+        assert_eq!(world.entities.len(), 5);
+        world.step();
+        assert!(world.entities.is_empty());
+    }
+
+    #[test]
+    fn test_leaving_the_world_counts_as_despawning() {
+        let mut world = PhysicsWorld::new(WorldConfig {
+            boundaries: Boundaries::all(BoundaryMode::Despawn),
+            ..Default::default()
+        });
+        let despawned = log();
+        let hook_log = despawned.clone();
+        world.on_despawn(move |entity| hook_log.borrow_mut().push(entity.uuid));
+
+        let mut shot = Entity::new(EntityType::Npc, (48.0, 5.0));
+        shot.vel = EuclidianVector::new(10.0, 0.0);
+        let shot = world.spawn(shot);
+        for _ in 0..20 {
+            world.step();
+        }
+        assert_eq!(*despawned.borrow(), vec![shot]);
+    }
+
+    #[test]
+    fn test_dead_and_hidden_entities_pass_through_everything() {
+        let wall = Entity::new(EntityType::Static, (10.0, 5.0));
+        let mut ghost = Entity::new(EntityType::Npc, (10.5, 5.0));
+        assert_eq!(collision::detect(&[wall.clone(), ghost.clone()]).len(), 1);
+
+        for state in [EntityState::Dead, EntityState::Hidden] {
+            ghost.state = state;
+            assert!(!ghost.collides_with(&wall));
+            assert!(collision::detect(&[wall.clone(), ghost.clone()]).is_empty());
+        }
+
+        // falling straight through the floor
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        ghost.pos = (10.0, 3.0);
+        world.entities = vec![Entity::new(EntityType::Static, (10.0, 5.0)), ghost];
+        for _ in 0..200 {
+            world.step();
+        }
+        assert_eq!(world.entities[1].pos.1, 9.0);
+    }
+
+    /// returns a world with an entity asleep on top of a static, and the static's uuid.
+    fn resting_on_a_static() -> (PhysicsWorld, Uuid) {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        let support = world.spawn(Entity::new(EntityType::Static, (10.0, 5.0)));
+        world.spawn(Entity::new(EntityType::Npc, (10.0, 4.0)));
+        for _ in 0..200 {
+            world.step();
+        }
+        assert!(world.entities[1].asleep);
+        (world, support)
+    }
+
+    #[test]
+    fn test_despawning_wakes_what_was_resting_on_the_entity() {
+        let (mut world, support) = resting_on_a_static();
+        world.despawn(support);
+        for _ in 0..200 {
+            world.step();
+        }
+        // fallen to the floor, rather than left floating where the static was
+        assert_eq!(world.entities.len(), 1);
+        assert_eq!(world.entities[0].pos.1, 9.0);
+    }
+
+    #[test]
+    fn test_dying_wakes_what_was_resting_on_the_entity() {
+        for state in [EntityState::Dead, EntityState::Hidden] {
+            let (mut world, _) = resting_on_a_static();
+            world.entities[0].state = state;
+            for _ in 0..200 {
+                world.step();
+            }
+            assert_eq!(world.entities[1].pos.1, 9.0);
+
+            // and settles again afterwards, despite lying next to the dead static
+            assert!(world.entities[1].asleep);
+        }
+    }
+
+    #[test]
+    fn test_despawning_removes_joints_to_the_entity() {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        let a = Entity::new(EntityType::Npc, (10.0, 5.0));
+        let b = Entity::new(EntityType::Npc, (13.0, 5.0));
+        world.joints = vec![
+            Joint::distance(Anchor::centroid(&a), Anchor::centroid(&b), 3.0),
+            Joint::revolute(&a, (10.0, 2.0)),
+            Joint::revolute(&b, (13.0, 2.0)),
+        ];
+        let a = world.spawn(a);
+        world.despawn(a);
+        world.step();
+        assert_eq!(world.joints, vec![Joint::revolute(&b, (13.0, 2.0))]);
+    }
+}
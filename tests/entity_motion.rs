@@ -3,34 +3,37 @@ mod test_entity_motion {
     use ascii_arcade::entity::{vector::EuclidianVector, *};
     #[test]
     fn test_target_pos_x_same_initial_xy() {
+        let config = WorldConfig::default();
         let initial: (f32, f32) = (5.0, 5.0);
         for i in 1..50 {
             // test from 1-to-5 in steps of 0.1 with a two-decimal resolution
             let mut entity = Entity::new(EntityType::Npc, initial);
             let i = 100.0 * (i as f32 * 0.1).round() / 100.0;
             let expected = (initial.0 + i, initial.1);
-            let force = entity.target_pos(expected.0, expected.1);
+            let force = entity.target_pos(expected.0, expected.1, &config);
             entity.apply_force(force.clone());
-            entity.update();
+            entity.update(&config);
             assert_eq!(entity.pos, expected);
         }
     }
     #[test]
     fn test_target_pos_y_same_initial_xy() {
+        let config = WorldConfig::default();
         let initial: (f32, f32) = (5.0, 2.0);
         for i in 1..50 {
             // test from 1-to-5 in steps of 0.1 with a two-decimal resolution
             let mut entity = Entity::new(EntityType::Npc, initial);
             let i = 100.0 * (i as f32 * 0.1).round() / 100.0;
             let expected = (initial.0, initial.1 + i);
-            let force = entity.target_pos(expected.0, expected.1);
+            let force = entity.target_pos(expected.0, expected.1, &config);
             entity.apply_force(force.clone());
-            entity.update();
+            entity.update(&config);
             assert_eq!(entity.pos, expected);
         }
     }
     #[test]
     fn test_target_pos_x_different_initial_xy() {
+        let config = WorldConfig::default();
         {
             let initial: (f32, f32) = (8.0, 7.0);
             for i in 1..50 {
@@ -38,15 +41,16 @@ mod test_entity_motion {
                 let mut entity = Entity::new(EntityType::Npc, initial);
                 let i = 100.0 * (i as f32 * 0.1).round() / 100.0;
                 let expected = (initial.0 - i, initial.1);
-                let force = entity.target_pos(expected.0, expected.1);
+                let force = entity.target_pos(expected.0, expected.1, &config);
                 entity.apply_force(force.clone());
-                entity.update();
+                entity.update(&config);
                 assert_eq!(entity.pos, expected);
             }
         }
     }
     #[test]
     fn test_target_pos_y_different_initial_xy() {
+        let config = WorldConfig::default();
         {
             let initial: (f32, f32) = (8.0, 7.0);
             for i in 1..50 {
@@ -54,43 +58,46 @@ mod test_entity_motion {
                 let mut entity = Entity::new(EntityType::Npc, initial);
                 let i = 100.0 * (i as f32 * 0.1).round() / 100.0;
                 let expected = (initial.0, initial.1 - i);
-                let force = entity.target_pos(expected.0, expected.1);
+                let force = entity.target_pos(expected.0, expected.1, &config);
                 entity.apply_force(force.clone());
-                entity.update();
+                entity.update(&config);
                 assert_eq!(entity.pos, expected);
             }
         }
     }
     #[test]
     fn test_target_vel_x_same_initial_xy() {
+        let config = WorldConfig::default();
         let initial: (f32, f32) = (5.0, 5.0);
         for i in 1..50 {
             // test from 1-to-5 in steps of 0.1 with a two-decimal resolution
             let mut entity = Entity::new(EntityType::Npc, initial);
             let i = 100.0 * (i as f32 * 0.1).round() / 100.0;
             let expected = EuclidianVector::new(initial.0 + i, initial.1);
-            let force = entity.target_vel(expected.x, expected.y);
+            let force = entity.target_vel(expected.x, expected.y, &config);
             entity.apply_force(force.clone());
-            entity.update();
+            entity.update(&config);
             assert_eq!(entity.vel, expected);
         }
     }
     #[test]
     fn test_target_vel_y_same_initial_xy() {
+        let config = WorldConfig::default();
         let initial: (f32, f32) = (5.0, 5.0);
         for i in 1..50 {
             // test from 1-to-5 in steps of 0.1 with a two-decimal resolution
             let mut entity = Entity::new(EntityType::Npc, initial);
             let i = 100.0 * (i as f32 * 0.1).round() / 100.0;
             let expected = EuclidianVector::new(initial.0, initial.1 + i);
-            let force = entity.target_vel(expected.x, expected.y);
+            let force = entity.target_vel(expected.x, expected.y, &config);
             entity.apply_force(force.clone());
-            entity.update();
+            entity.update(&config);
             assert_eq!(entity.vel, expected);
         }
     }
     #[test]
     fn test_target_vel_x_different_initial_xy() {
+        let config = WorldConfig::default();
         {
             let initial: (f32, f32) = (8.0, 7.0);
             for i in 1..50 {
@@ -98,15 +105,16 @@ mod test_entity_motion {
                 let mut entity = Entity::new(EntityType::Npc, initial);
                 let i = 100.0 * (i as f32 * 0.1).round() / 100.0;
                 let expected = EuclidianVector::new(initial.0 - i, initial.1);
-                let force = entity.target_vel(expected.x, expected.y);
+                let force = entity.target_vel(expected.x, expected.y, &config);
                 entity.apply_force(force.clone());
-                entity.update();
+                entity.update(&config);
                 assert_eq!(entity.vel, expected);
             }
         }
     }
     #[test]
     fn test_target_vel_y_different_initial_xy() {
+        let config = WorldConfig::default();
         {
             let initial: (f32, f32) = (8.0, 7.0);
             for i in 1..50 {
@@ -114,11 +122,160 @@ mod test_entity_motion {
                 let mut entity = Entity::new(EntityType::Npc, initial);
                 let i = 100.0 * (i as f32 * 0.1).round() / 100.0;
                 let expected = EuclidianVector::new(initial.0, initial.1 - i);
-                let force = entity.target_vel(expected.x, expected.y);
+                let force = entity.target_vel(expected.x, expected.y, &config);
                 entity.apply_force(force.clone());
-                entity.update();
+                entity.update(&config);
                 assert_eq!(entity.vel, expected);
             }
         }
     }
 }
+
+#[cfg(test)]
+mod test_entity_rotation {
+    use ascii_arcade::entity::{vector::EuclidianVector, *};
+
+    fn make_ball(pos: (f32, f32)) -> Entity {
+        let mut ball = Entity::new(EntityType::Npc, pos);
+        ball.hitbox = Hitbox::Circle;
+        ball
+    }
+
+    #[test]
+    fn test_torque_drives_angular_velocity() {
+        let config = WorldConfig::default();
+        let mut ball = make_ball((5.0, 5.0));
+        let inertia = ball.moment_of_inertia();
+        assert_eq!(inertia, 0.5 * 1.0 * 0.5 * 0.5);
+
+        assert!(ball.apply_torque(2.0));
+        ball.update(&config);
+        assert_eq!(ball.angular_vel, 2.0 / inertia * 0.01);
+        assert!(ball.orientation > 0.0);
+
+        // torque is consumed, so the ball keeps spinning at the same rate
+        ball.update(&config);
+        assert_eq!(ball.angular_vel, 2.0 / inertia * 0.01);
+    }
+
+    #[test]
+    fn test_off_center_forces_generate_torque() {
+        // pushing down on the right-hand side spins clockwise (on screen)
+        let mut ball = make_ball((5.0, 5.0));
+        ball.apply_force_at_point(EuclidianVector::new(0.0, 1.0), (5.5, 5.0));
+        assert_eq!(ball.torque, 0.5);
+        assert_eq!(ball.input_force, EuclidianVector::new(0.0, 1.0));
+
+        // pushing through the centroid doesn't
+        let mut ball = make_ball((5.0, 5.0));
+        ball.apply_force_at_point(EuclidianVector::new(3.0, 0.0), (4.5, 5.0));
+        assert_eq!(ball.torque, 0.0);
+    }
+
+    #[test]
+    fn test_boxes_do_not_rotate() {
+        let config = WorldConfig::default();
+        for hitbox in [
+            Hitbox::Square,
+            Hitbox::Aabb {
+                half_width: 2.0,
+                half_height: 0.5,
+            },
+        ] {
+            let mut entity = Entity::new(EntityType::Npc, (5.0, 5.0));
+            entity.hitbox = hitbox;
+            assert_eq!(entity.moment_of_inertia(), f32::INFINITY);
+            assert_eq!(entity.inverse_inertia(), 0.0);
+
+            // neither torques nor off-center forces spin them
+            assert!(!entity.apply_torque(100.0));
+            assert_eq!(entity.torque, 0.0);
+            entity.apply_force_at_point(EuclidianVector::new(0.0, 10.0), (6.0, 5.0));
+            entity.update(&config);
+            assert_eq!(entity.angular_vel, 0.0);
+            assert_eq!(entity.orientation, 0.0);
+        }
+    }
+
+    /// drops some debris onto the corner of a static, off-center, returning it after it lands
+    fn land_off_center(mut debris: Entity) -> Entity {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        debris.pos = (5.7, 3.0);
+        world.entities = vec![Entity::new(EntityType::Static, (5.0, 5.0)), debris];
+        for _ in 0..50 {
+            world.step();
+        }
+        world.entities.pop().unwrap()
+    }
+
+    #[test]
+    fn test_only_polygon_debris_tumbles_off_edges() {
+        // a square box lands flat, and slides off the corner without turning
+        let square = land_off_center(Entity::new(EntityType::Npc, (0.0, 0.0)));
+        assert_eq!(square.angular_vel, 0.0);
+        assert_eq!(square.orientation, 0.0);
+
+        // whereas a square polygon is tipped over the corner
+        let mut polygon = Entity::new(EntityType::Npc, (0.0, 0.0));
+        polygon.hitbox = Hitbox::Polygon(vec![(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)]);
+        let polygon = land_off_center(polygon);
+        assert!(polygon.angular_vel > 0.0);
+        assert!(polygon.orientation > 0.0);
+    }
+
+    #[test]
+    fn test_polygons_without_area_do_not_rotate() {
+        for vertices in [
+            vec![(-1.0, 0.0), (1.0, 0.0)],
+            vec![(-1.0, 0.0), (0.0, 0.0), (1.0, 0.0)],
+        ] {
+            let mut line = Entity::new(EntityType::Npc, (5.0, 5.0));
+            line.hitbox = Hitbox::Polygon(vertices);
+            assert_eq!(line.moment_of_inertia(), f32::INFINITY);
+            assert_eq!(line.inverse_inertia(), 0.0);
+
+            // landing on a static, off-center
+            let mut world = PhysicsWorld::new(WorldConfig::side_view());
+            line.pos = (5.6, 3.0);
+            world.entities = vec![Entity::new(EntityType::Static, (5.0, 5.0)), line];
+            for _ in 0..100 {
+                world.step();
+            }
+            let line = &world.entities[1];
+            assert!(line.orientation.is_finite() && line.angular_vel.is_finite());
+            assert!(line.pos.0.is_finite() && line.pos.1.is_finite());
+        }
+    }
+
+    #[test]
+    fn test_heading_is_counter_clockwise_from_x_axis() {
+        let mut ball = make_ball((5.0, 5.0));
+        assert_eq!(ball.heading(8), 0.0);
+        // a quarter turn clockwise on screen is pointing downwards
+        ball.orientation = std::f32::consts::FRAC_PI_2;
+        assert_eq!(ball.heading(8), 270.0);
+        ball.orientation = 0.8;
+        assert_eq!(ball.heading(8), 315.0);
+    }
+
+    #[test]
+    fn test_friction_makes_sliding_balls_roll() {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        world.entities = (1..40)
+            .map(|x| Entity::new(EntityType::Static, (x as f32, 6.0)))
+            .collect();
+        let mut ball = make_ball((5.0, 5.0));
+        ball.vel.x = 5.0;
+        world.entities.push(ball);
+
+        for _ in 0..200 {
+            world.step();
+        }
+
+        // rolling to the right is clockwise, without slipping against the floor
+        let ball = world.entities.last().unwrap();
+        assert!(ball.vel.x > 0.0);
+        assert!(ball.angular_vel > 0.0);
+        assert!((ball.vel.x - ball.angular_vel * ball.hit_radius).abs() < 0.1);
+    }
+}
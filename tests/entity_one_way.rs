@@ -0,0 +1,119 @@
+mod common;
+
+#[cfg(test)]
+mod tests_one_way {
+    use crate::common::open_side_view;
+    use ascii_arcade::entity::{collision, vector::EuclidianVector, *};
+
+    fn platform(pos: (f32, f32)) -> Entity {
+        let mut platform = Entity::new(EntityType::Static, pos);
+        platform.hitbox = Hitbox::Aabb {
+            half_width: 5.0,
+            half_height: 0.5,
+        };
+        platform.one_way = Some(EuclidianVector::new(0.0, -1.0));
+        platform
+    }
+
+    #[test]
+    fn test_entities_land_on_one_way_platforms() {
+        let mut world = PhysicsWorld::new(open_side_view());
+        let player = Entity::new(EntityType::Player, (50.0, 40.0));
+        world.entities = vec![platform((50.0, 50.0)), player];
+
+        for _ in 0..300 {
+            world.step();
+        }
+        let player = &world.entities[1];
+        assert!((player.pos.1 - 49.0).abs() < 0.05);
+        assert!(player.grounded);
+        assert_eq!(world.entities[0].pos, (50.0, 50.0));
+    }
+
+    #[test]
+    fn test_entities_jump_up_through_one_way_platforms() {
+        let mut world = PhysicsWorld::new(open_side_view());
+        let mut player = Entity::new(EntityType::Player, (50.0, 56.0));
+        player.vel = EuclidianVector::new(0.0, -15.0);
+        world.entities = vec![platform((50.0, 50.0)), player];
+
+        // rising up through the platform, unhindered
+        let mut highest: f32 = 56.0;
+        for _ in 0..400 {
+            world.step();
+            highest = highest.min(world.entities[1].pos.1);
+        }
+        assert!(highest < 46.0);
+
+        // then falling back down onto it
+        let player = &world.entities[1];
+        assert!((player.pos.1 - 49.0).abs() < 0.05);
+        assert!(player.grounded);
+    }
+
+    #[test]
+    fn test_one_way_platforms_are_passed_through_sideways() {
+        let mut world = PhysicsWorld::new(open_side_view());
+        world.config.gravity = EuclidianVector::new(0.0, 0.0);
+        let mut npc = Entity::new(EntityType::Npc, (40.0, 50.0));
+        npc.vel = EuclidianVector::new(10.0, 0.0);
+        world.entities = vec![platform((50.0, 50.0)), npc];
+
+        for _ in 0..200 {
+            world.step();
+        }
+        // out the other side of the platform, at full speed
+        assert!(world.entities[1].pos.0 > 56.0);
+        assert_eq!(world.entities[1].vel.x, 10.0);
+    }
+
+    #[test]
+    fn test_one_way_contacts_push_out_of_the_open_side() {
+        // barely overlapping the edge of the platform, which would normally push sideways
+        let platform = platform((50.0, 50.0));
+        let npc = Entity::new(EntityType::Npc, (55.4, 49.2));
+        let entities = vec![platform, npc];
+
+        let starts = vec![(50.0, 50.0), (55.4, 48.9)];
+        let contacts = collision::one_way(&entities, &starts, collision::detect(&entities));
+        assert_eq!(contacts.len(), 1);
+        assert_eq!(contacts[0].manifold.normal, EuclidianVector::new(0.0, -1.0));
+        assert!((contacts[0].manifold.depth - 0.2).abs() < 1e-4);
+
+        // the same overlap, reached from below, isn't a contact at all
+        let starts = vec![(50.0, 50.0), (55.4, 50.5)];
+        assert!(collision::one_way(&entities, &starts, collision::detect(&entities)).is_empty());
+
+        // and the normal is flipped when the platform comes second
+        let flipped = vec![entities[1].clone(), entities[0].clone()];
+        let starts = vec![(55.4, 48.9), (50.0, 50.0)];
+        let contacts = collision::one_way(&flipped, &starts, collision::detect(&flipped));
+        assert_eq!(contacts[0].manifold.normal, EuclidianVector::new(0.0, 1.0));
+    }
+
+    #[test]
+    fn test_bullets_only_stop_at_the_open_side_of_one_way_walls() {
+        let mut world = PhysicsWorld::new(open_side_view());
+        world.config.gravity = EuclidianVector::new(0.0, 0.0);
+        world.config.max_vel = 1_000.0;
+        let mut wall = Entity::new(EntityType::Static, (50.0, 50.0));
+        wall.hitbox = Hitbox::Aabb {
+            half_width: 0.1,
+            half_height: 5.0,
+        };
+        wall.one_way = Some(EuclidianVector::new(-1.0, 0.0));
+
+        let mut from_left = Entity::new(EntityType::Npc, (45.0, 48.0));
+        from_left.vel = EuclidianVector::new(500.0, 0.0);
+        from_left.bullet = true;
+        from_left.restitution = 0.0;
+        let mut from_right = Entity::new(EntityType::Npc, (53.0, 52.0));
+        from_right.vel = EuclidianVector::new(-500.0, 0.0);
+        from_right.bullet = true;
+        world.entities = vec![wall, from_left, from_right];
+
+        world.step();
+        assert!((world.entities[1].pos.0 - 49.4).abs() < 0.05);
+        assert!(world.entities[2].pos.0 < 49.0);
+    }
+}
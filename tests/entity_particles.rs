@@ -0,0 +1,280 @@
+mod common;
+
+#[cfg(test)]
+mod tests_particles {
+    use crate::common::{approx, open_side_view};
+    use ascii_arcade::entity::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn test_emitters_emit_at_their_rate() {
+        let config = open_side_view();
+        let mut system = ParticleSystem::new(1);
+        system.add_emitter(Emitter {
+            pos: (50.0, 50.0),
+            rate: 30.0,
+            lifetime: (10.0, 10.0),
+            gravity_scale: 0.0,
+            ..Default::default()
+        });
+
+        // 30 per second, at 100 steps per second, is a particle every 3-4 steps
+        for _ in 0..100 {
+            system.update(&config, &[]);
+        }
+        assert!((29..=30).contains(&system.particles().len()));
+    }
+
+    #[test]
+    fn test_particles_are_emitted_within_the_spread_and_speed_range() {
+        let mut system = ParticleSystem::new(2);
+        let emitter = system.add_emitter(Emitter {
+            pos: (50.0, 50.0),
+            direction: -FRAC_PI_2,
+            spread: 0.5,
+            speed: (2.0, 4.0),
+            lifetime: (1.0, 1.0),
+            ..Default::default()
+        });
+        system.burst(emitter, 200);
+        assert_eq!(system.particles().len(), 200);
+
+        for particle in system.particles() {
+            let speed = particle.vel.magnitude();
+            assert!((2.0 - 1e-4..=4.0 + 1e-4).contains(&speed));
+            // upwards on screen, within 0.5 radians either side
+            let angle = particle.vel.y.atan2(particle.vel.x);
+            assert!((angle + FRAC_PI_2).abs() <= 0.5 + 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_particles_expire_after_their_lifetime() {
+        let config = open_side_view();
+        let mut system = ParticleSystem::new(3);
+        let emitter = system.add_emitter(Emitter {
+            pos: (50.0, 50.0),
+            lifetime: (0.1, 0.2),
+            gravity_scale: 0.0,
+            ..Default::default()
+        });
+        system.burst(emitter, 50);
+
+        for _ in 0..5 {
+            system.update(&config, &[]);
+        }
+        assert_eq!(system.particles().len(), 50);
+        for _ in 0..16 {
+            system.update(&config, &[]);
+        }
+        assert!(system.particles().is_empty());
+    }
+
+    #[test]
+    fn test_particles_fade_through_the_colour_ramp() {
+        let emitter = Emitter {
+            ramp: vec![(255, 255, 0), (255, 0, 0), (0, 0, 0)],
+            ..Default::default()
+        };
+        assert_eq!(emitter.colour_at(0.0), (255, 255, 0));
+        assert_eq!(emitter.colour_at(0.25), (255, 128, 0));
+        assert_eq!(emitter.colour_at(0.5), (255, 0, 0));
+        assert_eq!(emitter.colour_at(1.0), (0, 0, 0));
+
+        let config = open_side_view();
+        let mut system = ParticleSystem::new(4);
+        let idx = system.add_emitter(Emitter {
+            pos: (50.0, 50.0),
+            lifetime: (1.0, 1.0),
+            ..emitter
+        });
+        system.burst(idx, 1);
+        assert_eq!(system.particles()[0].colour, (255, 255, 0));
+        for _ in 0..50 {
+            system.update(&config, &[]);
+        }
+        assert_eq!(system.particles()[0].colour, (255, 0, 0));
+    }
+
+    #[test]
+    fn test_particles_fall_under_scaled_gravity() {
+        let config = open_side_view();
+        let mut system = ParticleSystem::new(5);
+        for gravity_scale in [1.0, 0.0, -0.5] {
+            system.add_emitter(Emitter {
+                pos: (50.0, 50.0),
+                speed: (0.0, 0.0),
+                gravity_scale,
+                ..Default::default()
+            });
+        }
+        for emitter in 0..3 {
+            system.burst(emitter, 1);
+        }
+        for _ in 0..50 {
+            system.update(&config, &[]);
+        }
+
+        let vels: Vec<f32> = system.particles().iter().map(|p| p.vel.y).collect();
+        assert!(approx(vels[0], 4.905, 1e-3));
+        assert_eq!(vels[1], 0.0);
+        assert!(approx(vels[2], -2.4525, 1e-3));
+    }
+
+    #[test]
+    fn test_colliding_particles_bounce_off_statics() {
+        let config = open_side_view();
+        let mut floor = Entity::new(EntityType::Static, (50.0, 60.0));
+        floor.hitbox = Hitbox::Aabb {
+            half_width: 20.0,
+            half_height: 1.0,
+        };
+        let entities = vec![floor];
+
+        let mut system = ParticleSystem::new(6);
+        for collide in [true, false] {
+            system.add_emitter(Emitter {
+                pos: (50.0, 50.0),
+                direction: FRAC_PI_2,
+                spread: 0.0,
+                speed: (10.0, 10.0),
+                lifetime: (5.0, 5.0),
+                collide,
+                bounce: 0.5,
+                ..Default::default()
+            });
+        }
+        system.burst(0, 1);
+        system.burst(1, 1);
+
+        let mut bounced = false;
+        for _ in 0..150 {
+            system.update(&config, &entities);
+            let particle = &system.particles()[0];
+            assert!(particle.pos.1 <= 59.0 + 1e-3);
+            bounced |= particle.vel.y < 0.0;
+        }
+        assert!(bounced);
+        // the other particle fell straight through the floor
+        assert!(system.particles()[1].pos.1 > 61.0);
+    }
+
+    #[test]
+    fn test_particles_emitted_inside_a_wall_pass_out_of_it() {
+        let config = open_side_view();
+        let entities = vec![Entity::new(EntityType::Static, (50.0, 50.0))];
+
+        // sparks thrown up from the middle of a newly placed wall
+        let mut system = ParticleSystem::new(9);
+        let emitter = system.add_emitter(Emitter {
+            pos: (50.0, 50.0),
+            direction: -FRAC_PI_2,
+            spread: 1.0,
+            speed: (4.0, 10.0),
+            lifetime: (2.0, 2.0),
+            collide: true,
+            ..Default::default()
+        });
+        system.burst(emitter, 20);
+        for _ in 0..20 {
+            system.update(&config, &entities);
+        }
+        assert_eq!(system.particles().len(), 20);
+        for particle in system.particles() {
+            assert!(particle.pos.1 < 49.5);
+        }
+
+        // and land back on top of it, rather than falling back inside
+        for _ in 0..150 {
+            system.update(&config, &entities);
+            for particle in system.particles() {
+                let (dx, dy) = (particle.pos.0 - 50.0, particle.pos.1 - 50.0);
+                assert!(dx.abs() > 0.5 || dy < -0.5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_particles_carry_on_after_their_emitter_is_removed() {
+        let config = open_side_view();
+        let mut floor = Entity::new(EntityType::Static, (50.0, 60.0));
+        floor.hitbox = Hitbox::Aabb {
+            half_width: 20.0,
+            half_height: 1.0,
+        };
+        let entities = vec![floor];
+
+        let mut system = ParticleSystem::new(8);
+        for (ramp, collide) in [((0, 0, 255), false), ((255, 0, 0), true)] {
+            system.add_emitter(Emitter {
+                pos: (50.0, 50.0),
+                direction: FRAC_PI_2,
+                spread: 0.0,
+                speed: (10.0, 10.0),
+                lifetime: (5.0, 5.0),
+                ramp: vec![ramp],
+                gravity_scale: 0.5,
+                collide,
+                ..Default::default()
+            });
+        }
+        system.burst(1, 1);
+
+        // removing an earlier emitter moves the particle's own emitter down an index
+        system.emitters.remove(0);
+        for _ in 0..150 {
+            system.update(&config, &entities);
+            let particle = &system.particles()[0];
+            assert_eq!(particle.colour, (255, 0, 0));
+            assert!(particle.pos.1 <= 59.0 + 1e-3);
+        }
+
+        // and once every emitter is gone
+        system.emitters.clear();
+        system.update(&config, &entities);
+        assert_eq!(system.particles()[0].colour, (255, 0, 0));
+    }
+
+    #[test]
+    fn test_particles_outside_the_bounds_are_removed() {
+        let config = open_side_view();
+        let mut system = ParticleSystem::new(7);
+        let emitter = system.add_emitter(Emitter {
+            pos: (99.0, 50.0),
+            direction: 0.0,
+            spread: 0.0,
+            speed: (10.0, 10.0),
+            lifetime: (5.0, 5.0),
+            gravity_scale: 0.0,
+            ..Default::default()
+        });
+        system.burst(emitter, 1);
+        for _ in 0..20 {
+            system.update(&config, &[]);
+        }
+        assert!(system.particles().is_empty());
+    }
+
+    #[test]
+    fn test_worlds_update_their_particles_repeatably() {
+        let run = || {
+            let mut world = PhysicsWorld::new(WorldConfig {
+                seed: Some(42),
+                ..open_side_view()
+            });
+            world.particles.add_emitter(Emitter {
+                pos: (50.0, 50.0),
+                rate: 100.0,
+                speed: (1.0, 5.0),
+                ..Default::default()
+            });
+            for _ in 0..20 {
+                world.step();
+            }
+            world.particles.particles().to_vec()
+        };
+        let particles = run();
+        assert_eq!(particles.len(), 20);
+        assert_eq!(particles, run());
+    }
+}
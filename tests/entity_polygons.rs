@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod test_entity_slopes {
+    use ascii_arcade::entity::*;
+
+    #[test]
+    fn test_entities_slide_down_ramps() {
+        // a ramp sloping down to the right, with a ball dropped onto it
+        let mut ramp = Entity::new(EntityType::Static, (10.0, 6.0));
+        ramp.hitbox = Hitbox::Polygon(vec![(-4.0, -2.0), (4.0, 2.0), (-4.0, 2.0)]);
+        let mut ball = Entity::new(EntityType::Npc, (9.0, 4.5));
+        ball.hitbox = Hitbox::Circle;
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        world.entities = vec![ramp, ball];
+
+        let mut touched = false;
+        for _ in 0..100 {
+            world.step();
+            touched |= world.entities[1].grounded;
+        }
+
+        // the ball lands on the ramp and rolls down it, rather than falling through it
+        let ball = &world.entities[1];
+        assert!(touched);
+        assert!(ball.vel.x > 0.0);
+        assert!(ball.pos.0 > 9.0);
+        let surface = 6.0 + (ball.pos.0 - 10.0) * 0.5;
+        assert!(ball.pos.1 < surface);
+        assert!(ball.angular_vel > 0.0);
+    }
+
+    #[test]
+    fn test_polygon_entities_rotate_from_off_center_contacts() {
+        // a box landing on one of its corners gets knocked into a spin
+        let mut floor = Entity::new(EntityType::Static, (10.0, 8.0));
+        floor.hitbox = Hitbox::Polygon(vec![(-8.0, -0.5), (8.0, -0.5), (8.0, 0.5), (-8.0, 0.5)]);
+        let mut crate_ = Entity::new(EntityType::Npc, (10.0, 5.0));
+        crate_.hitbox = Hitbox::Polygon(vec![(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]);
+        crate_.orientation = 0.3;
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        world.entities = vec![floor, crate_];
+
+        for _ in 0..100 {
+            world.step();
+        }
+        assert!(world.entities[1].orientation != 0.3);
+        assert!(world.entities[1].pos.1 < 7.5);
+    }
+}
@@ -0,0 +1,156 @@
+mod common;
+
+#[cfg(test)]
+mod tests_primitive_raycasts {
+    use crate::common::approx;
+    use ascii_arcade::entity::primitives::*;
+    use ascii_arcade::entity::vector::EuclidianVector;
+    use std::f32::consts::FRAC_PI_4;
+
+    const RIGHT: EuclidianVector = EuclidianVector { x: 1.0, y: 0.0 };
+
+    #[test]
+    fn test_circle_raycast() {
+        let circle = Circle::new(&(5.0, 0.0), &1.0);
+
+        let (distance, normal) = circle.raycast(&(0.0, 0.0), &RIGHT).unwrap();
+        assert_eq!(distance, 4.0);
+        assert_eq!((normal.x, normal.y), (-1.0, 0.0));
+
+        // grazing past the top, and pointing away from it
+        assert!(circle.raycast(&(0.0, -1.1), &RIGHT).is_none());
+        assert!(circle.raycast(&(7.0, 0.0), &RIGHT).is_none());
+
+        // starting inside
+        let (distance, normal) = circle.raycast(&(5.5, 0.0), &RIGHT).unwrap();
+        assert_eq!(distance, 0.0);
+        assert_eq!((normal.x, normal.y), (-1.0, 0.0));
+    }
+
+    #[test]
+    fn test_aabb_raycast() {
+        let wall = Aabb::new(&(5.0, 0.0), 0.5, 3.0);
+
+        let (distance, normal) = wall.raycast(&(0.0, 2.0), &RIGHT).unwrap();
+        assert_eq!(distance, 4.5);
+        assert_eq!((normal.x, normal.y), (-1.0, 0.0));
+
+        // diagonally onto the top of the wall
+        let down_right = EuclidianVector::new(1.0, 1.0).unit();
+        let (distance, normal) = wall.raycast(&(1.0, -7.0), &down_right).unwrap();
+        assert!(approx(distance, 4.0 * 2.0_f32.sqrt(), 1e-4));
+        assert_eq!((normal.x, normal.y), (0.0, -1.0));
+
+        // passing over the top of the wall
+        assert!(wall.raycast(&(0.0, -3.5), &RIGHT).is_none());
+    }
+
+    #[test]
+    fn test_polygon_raycast() {
+        let square = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)];
+        let diamond = Polygon::new(&(5.0, 0.0), &square, FRAC_PI_4);
+
+        let (distance, normal) = diamond.raycast(&(0.0, 0.0), &RIGHT).unwrap();
+        assert!(approx(distance, 5.0 - 2.0_f32.sqrt(), 1e-4));
+        assert!(
+            approx(normal.x, -FRAC_PI_4.cos(), 1e-4)
+                && approx(normal.y.abs(), FRAC_PI_4.sin(), 1e-4)
+        );
+
+        // the same shape, with its vertices listed the other way round
+        let reversed: Vec<(f32, f32)> = square.iter().rev().copied().collect();
+        let diamond = Polygon::new(&(5.0, 0.0), &reversed, FRAC_PI_4);
+        let (other_distance, _) = diamond.raycast(&(0.0, 0.0), &RIGHT).unwrap();
+        assert!(approx(distance, other_distance, 1e-4));
+
+        // passing just beyond the diamond's top corner
+        assert!(diamond.raycast(&(0.0, -1.5), &RIGHT).is_none());
+    }
+
+    #[test]
+    fn test_polygons_without_three_vertices_are_never_hit() {
+        for vertices in [vec![], vec![(0.0, 0.0)], vec![(0.0, -1.0), (0.0, 1.0)]] {
+            let polygon = Polygon::new(&(5.0, 0.0), &vertices, 0.0);
+            assert!(polygon.raycast(&(0.0, 0.0), &RIGHT).is_none());
+            assert!(polygon.raycast(&(5.0, 0.0), &RIGHT).is_none());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_entity_raycasts {
+    use ascii_arcade::entity::raycast::Ray;
+    use ascii_arcade::entity::vector::EuclidianVector;
+    use ascii_arcade::entity::*;
+
+    fn scene() -> Vec<Entity> {
+        let mut ball = Entity::new(EntityType::Npc, (8.0, 5.0));
+        ball.hitbox = Hitbox::Circle;
+        let mut wall = Entity::new(EntityType::Static, (12.0, 5.0));
+        wall.hitbox = Hitbox::Aabb {
+            half_width: 0.5,
+            half_height: 4.0,
+        };
+        let player = Entity::new(EntityType::Player, (2.0, 5.0));
+        vec![wall, ball, player]
+    }
+
+    #[test]
+    fn test_rays_hit_the_nearest_entity() {
+        let entities = scene();
+        let ray = Ray::new((0.0, 5.0), EuclidianVector::new(3.0, 0.0), f32::INFINITY);
+
+        // the player is nearest, then the ball, then the wall
+        let hit = ray.cast(&entities).unwrap();
+        assert_eq!(hit.uuid, entities[2].uuid);
+        assert_eq!(hit.point, (1.5, 5.0));
+        assert_eq!(hit.distance, 1.5);
+        assert_eq!((hit.normal.x, hit.normal.y), (-1.0, 0.0));
+
+        let hit = ray
+            .cast_filtered(&entities, |e| e.id != EntityType::Player)
+            .unwrap();
+        assert_eq!(hit.uuid, entities[1].uuid);
+        assert_eq!(hit.distance, 7.5);
+
+        let hit = ray
+            .cast_filtered(&entities, |e| e.id == EntityType::Static)
+            .unwrap();
+        assert_eq!(hit.uuid, entities[0].uuid);
+        assert_eq!(hit.point, (11.5, 5.0));
+    }
+
+    #[test]
+    fn test_segments_stop_at_their_end() {
+        let entities = scene();
+
+        // line of sight from the player to a point short of the ball
+        let sight = Ray::segment((2.0, 5.0), (7.0, 5.0));
+        assert!(sight
+            .cast_filtered(&entities, |e| e.id != EntityType::Player)
+            .is_none());
+
+        // line of sight from the player over the ball, but into the wall
+        let sight = Ray::segment((2.0, 3.0), (14.0, 3.0));
+        let hit = sight
+            .cast_filtered(&entities, |e| e.id != EntityType::Player)
+            .unwrap();
+        assert_eq!(hit.uuid, entities[0].uuid);
+        assert_eq!(hit.distance, 9.5);
+    }
+
+    #[test]
+    fn test_picking_entities_at_a_point() {
+        let entities = scene();
+
+        let hit = Ray::segment((8.2, 4.9), (8.2, 4.9))
+            .cast(&entities)
+            .unwrap();
+        assert_eq!(hit.uuid, entities[1].uuid);
+        assert_eq!(hit.distance, 0.0);
+
+        assert!(Ray::segment((5.0, 5.0), (5.0, 5.0))
+            .cast(&entities)
+            .is_none());
+    }
+}
@@ -0,0 +1,177 @@
+#[cfg(test)]
+mod tests_rigid_body {
+    use ascii_arcade::entity::{collision, vector::EuclidianVector, *};
+
+    /// returns the offsets of each part of a body from its first part
+    fn offsets(parts: &[Entity]) -> Vec<(f32, f32)> {
+        parts
+            .iter()
+            .map(|p| (p.pos.0 - parts[0].pos.0, p.pos.1 - parts[0].pos.1))
+            .collect()
+    }
+
+    #[test]
+    fn test_body_properties_combine_its_parts() {
+        let mut body = RigidBody::rectangle(EntityType::Npc, (2.0, 2.0), 3, 1);
+        body.parts[2].mass = 2.0;
+        assert_eq!(body.parts.len(), 3);
+        assert!(body.parts.iter().all(|p| p.body == Some(body.uuid)));
+        assert_eq!(body.mass(), 4.0);
+        assert_eq!(body.center_of_mass(), Some((3.25, 2.0)));
+    }
+
+    #[test]
+    fn test_center_of_mass_without_mass() {
+        // massless parts are weighted equally
+        let mut body = RigidBody::rectangle(EntityType::Npc, (2.0, 2.0), 3, 1);
+        body.parts.iter_mut().for_each(|part| part.mass = 0.0);
+        assert_eq!(body.center_of_mass(), Some((3.0, 2.0)));
+
+        // and a body without any parts has no center at all
+        body.parts.clear();
+        assert_eq!(body.center_of_mass(), None);
+    }
+
+    #[test]
+    fn test_clamped_bodies_are_moved_whole_within_bounds() {
+        let bounds = WorldConfig::default().bounds;
+        let mut body = RigidBody::rectangle(EntityType::Npc, (bounds.right, bounds.bottom), 2, 2);
+        let before = offsets(&body.parts);
+        body.clamp(&bounds);
+
+        // the far corner is pulled back inside, and the body keeps its shape
+        assert_eq!(body.parts[3].pos, (bounds.right, bounds.bottom));
+        assert_eq!(offsets(&body.parts), before);
+    }
+
+    #[test]
+    fn test_parts_share_forces_and_velocity() {
+        let mut world = PhysicsWorld::default();
+        world.entities = RigidBody::rectangle(EntityType::Npc, (5.0, 5.0), 2, 2).parts;
+        let before = offsets(&world.entities);
+
+        // push on just one corner of the body
+        world.entities[3].apply_force(EuclidianVector::new(400.0, 0.0));
+        world.step();
+
+        // the whole body accelerates, as if the force was applied to its total mass
+        for part in world.entities.iter() {
+            assert_eq!(part.vel.x, 400.0 / 4.0 * 0.01);
+        }
+        assert_eq!(offsets(&world.entities), before);
+    }
+
+    #[test]
+    fn test_parts_do_not_collide_with_one_another() {
+        let body = RigidBody::new(EntityType::Npc, (5.0, 5.0), &[(0.0, 0.0), (0.5, 0.0)]);
+        assert!(collision::detect(&body.parts).is_empty());
+    }
+
+    #[test]
+    fn test_body_collides_as_a_unit() {
+        // a crate sliding into a single npc
+        let mut entities = RigidBody::rectangle(EntityType::Npc, (5.0, 5.0), 2, 2).parts;
+        for part in entities.iter_mut() {
+            part.vel.x = 6.0;
+            part.restitution = 1.0;
+        }
+        let mut npc = Entity::new(EntityType::Npc, (6.85, 5.0));
+        npc.restitution = 1.0;
+        entities.push(npc);
+        let before = offsets(&entities[..4]);
+
+        let contacts = collision::detect(&entities);
+        assert_eq!(contacts.len(), 1);
+        collision::resolve(&mut entities, &contacts);
+
+        // an elastic collision between a mass of 4 (at 6.0) and a mass of 1 (at rest)
+        for part in entities[..4].iter() {
+            assert!((part.vel.x - 3.6).abs() < 1e-5);
+        }
+        assert!((entities[4].vel.x - 9.6).abs() < 1e-5);
+        assert_eq!(offsets(&entities[..4]), before);
+    }
+
+    #[test]
+    fn test_body_comes_to_rest_on_a_platform() {
+        let mut entities = RigidBody::rectangle(EntityType::Static, (2.0, 7.0), 8, 1).parts;
+        entities.extend(RigidBody::rectangle(EntityType::Npc, (4.0, 3.0), 3, 2).parts);
+        let before = offsets(&entities[8..]);
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        world.entities = entities;
+
+        for _ in 0..300 {
+            world.step();
+        }
+
+        let parts = &world.entities[8..];
+        for part in parts {
+            assert!(part.grounded);
+            assert!(part.vel.magnitude() < 0.5);
+        }
+        // the bottom of the body rests on the platform
+        assert!((parts[3].pos.1 - 6.0).abs() < 0.1);
+        for (actual, expect) in offsets(parts).iter().zip(before.iter()) {
+            assert!((actual.0 - expect.0).abs() < 1e-4);
+            assert!((actual.1 - expect.1).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_body_stays_within_the_window() {
+        // a platform falling onto the floor of the world
+        let entities = RigidBody::rectangle(EntityType::Npc, (5.0, 5.0), 3, 2).parts;
+        let before = offsets(&entities);
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        world.entities = entities;
+        for _ in 0..300 {
+            world.step();
+        }
+        let floor = world.config.bounds.bottom;
+        let entities = &world.entities;
+        assert!(entities.iter().all(|p| p.pos.1 <= floor && p.grounded));
+        assert_eq!(entities[5].pos.1, floor);
+        for (actual, expect) in offsets(entities).iter().zip(before.iter()) {
+            assert!((actual.0 - expect.0).abs() < 1e-4);
+            assert!((actual.1 - expect.1).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_bodies_sliding_along_the_window_floor_slow_down() {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        let floor = world.config.bounds.bottom;
+        world.spawn_body(RigidBody::rectangle(
+            EntityType::Npc,
+            (5.0, floor - 1.0),
+            2,
+            2,
+        ));
+        world.spawn(Entity::new(EntityType::Npc, (30.0, floor)));
+        for entity in world.entities.iter_mut() {
+            entity.vel.x = 5.0;
+        }
+        for _ in 0..50 {
+            world.step();
+        }
+
+        // slowed by the floor just as much as a single entity is
+        let single = world.entities[4].vel.x;
+        assert!(single < 4.0);
+        for part in world.entities[..4].iter() {
+            assert!(part.grounded);
+            assert!((part.vel.x - single).abs() < 0.1);
+        }
+    }
+
+    #[test]
+    fn test_single_part_body_is_simulated() {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        world.spawn_body(RigidBody::new(EntityType::Npc, (10.0, 3.0), &[(0.0, 0.0)]));
+        for _ in 0..50 {
+            world.step();
+        }
+        // falling under gravity like any other entity
+        assert!(world.entities[0].pos.1 > 4.0);
+    }
+}
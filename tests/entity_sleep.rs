@@ -0,0 +1,188 @@
+mod common;
+
+#[cfg(test)]
+mod tests_sleep {
+    use crate::common::open_side_view;
+    use ascii_arcade::entity::{
+        collision, events::ContactPhase, sleep, vector::EuclidianVector, *,
+    };
+
+    /// a static floor with a stack of npcs on it at each of the given x-positions.
+    fn make_piles(xs: &[f32]) -> PhysicsWorld {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        world.entities = (1..40)
+            .map(|x| Entity::new(EntityType::Static, (x as f32, 8.0)))
+            .collect();
+        for &x in xs {
+            for y in [7.0, 6.0, 5.0] {
+                world.entities.push(Entity::new(EntityType::Npc, (x, y)));
+            }
+        }
+        world
+    }
+
+    fn npcs(world: &PhysicsWorld) -> impl Iterator<Item = &Entity> {
+        world.entities.iter().filter(|e| e.id == EntityType::Npc)
+    }
+
+    #[test]
+    fn test_settled_piles_fall_asleep() {
+        let mut world = make_piles(&[5.0, 20.0]);
+        for _ in 0..300 {
+            world.step();
+        }
+        assert!(npcs(&world).all(|e| e.asleep && e.grounded));
+        assert!(npcs(&world).all(|e| e.vel.magnitude() == 0.0));
+
+        // asleep entities are left out of the collision checks
+        assert!(collision::detect(&world.entities).iter().all(|c| world
+            .entities
+            .iter()
+            .any(|e| e.uuid == c.a && e.id == EntityType::Static)));
+
+        // and stay exactly where they are
+        let before: Vec<(f32, f32)> = world.entities.iter().map(|e| e.pos).collect();
+        for _ in 0..100 {
+            world.step();
+        }
+        let after: Vec<(f32, f32)> = world.entities.iter().map(|e| e.pos).collect();
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn test_asleep_worlds_keep_reporting_resting_contacts() {
+        let mut world = make_piles(&[5.0, 20.0]);
+        for _ in 0..300 {
+            world.step();
+        }
+        world.drain_events();
+
+        world.step();
+        let events = world.drain_events();
+        assert!(npcs(&world).all(|e| e.asleep));
+        assert!(!events.is_empty());
+        assert!(events.iter().all(|e| e.phase == ContactPhase::Stay));
+        assert!(npcs(&world).all(|npc| events.iter().any(|e| e.involves(npc.uuid))));
+    }
+
+    #[test]
+    fn test_tall_stacks_fall_asleep() {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        world.entities = (1..40)
+            .map(|x| Entity::new(EntityType::Static, (x as f32, 8.0)))
+            .collect();
+        for (x, height) in [(5.0, 4), (20.0, 6)] {
+            for i in 1..=height {
+                let y = 8.0 - i as f32;
+                world.entities.push(Entity::new(EntityType::Npc, (x, y)));
+            }
+        }
+        for _ in 0..300 {
+            world.step();
+        }
+        assert!(npcs(&world).all(|e| e.asleep && e.grounded));
+    }
+
+    #[test]
+    fn test_large_piles_fall_asleep() {
+        // rows of npcs thrown every which way down onto a static floor
+        let mut world = PhysicsWorld::new(open_side_view());
+        world.entities = (1..=40)
+            .map(|x| Entity::new(EntityType::Static, (x as f32, 90.0)))
+            .collect();
+        for i in 0..200 {
+            let x = 2.0 + (i % 37) as f32 + 0.1 * (i % 7) as f32;
+            let y = 83.0 - (i / 37) as f32 * 1.2 + (i % 6) as f32 + 0.05 * (i % 11) as f32;
+            let mut npc = Entity::new(EntityType::Npc, (x, y));
+            npc.vel.x = (i % 5) as f32 * 3.0 - 6.0;
+            npc.vel.y = (i % 3) as f32 * 6.0 - 6.0;
+            world.entities.push(npc);
+        }
+        for _ in 0..3000 {
+            world.step();
+        }
+        assert!(npcs(&world).all(|e| e.asleep));
+    }
+
+    #[test]
+    fn test_sleeping_can_be_disabled() {
+        let mut world = make_piles(&[5.0]);
+        world.config.sleep_ticks = None;
+        for _ in 0..300 {
+            world.step();
+        }
+        assert!(npcs(&world).all(|e| !e.asleep));
+    }
+
+    #[test]
+    fn test_forces_wake_the_whole_island() {
+        let mut world = make_piles(&[5.0, 20.0]);
+        for _ in 0..300 {
+            world.step();
+        }
+
+        // shove the bottom of the first pile sideways
+        world.entities[39].apply_force(EuclidianVector::new(300.0, 0.0));
+        world.step();
+
+        let (first, second) = world.entities[39..].split_at(3);
+        assert!(first.iter().all(|e| !e.asleep));
+        assert!(first[0].vel.x > 0.0);
+        assert!(second.iter().all(|e| e.asleep));
+    }
+
+    #[test]
+    fn test_contacts_wake_asleep_entities() {
+        let mut world = make_piles(&[5.0]);
+        for _ in 0..300 {
+            world.step();
+        }
+
+        // a ball thrown into the top of the pile
+        let mut ball = Entity::new(EntityType::Npc, (2.0, 5.0));
+        ball.hitbox = Hitbox::Circle;
+        ball.vel.x = 10.0;
+        world.entities.push(ball);
+        let mut woken = false;
+        for _ in 0..30 {
+            world.step();
+            woken |= world.entities[41].vel.x > 0.0;
+        }
+        assert!(woken);
+        assert!(world.entities[41].pos.0 > 5.0);
+    }
+
+    #[test]
+    fn test_piles_on_the_window_floor_fall_asleep() {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        let floor = world.config.bounds.bottom;
+        for y in [floor, floor - 1.0, floor - 2.0] {
+            world.entities.push(Entity::new(EntityType::Npc, (10.0, y)));
+        }
+        for _ in 0..300 {
+            world.step();
+        }
+        assert!(npcs(&world).all(|e| e.asleep && e.grounded));
+        assert!(npcs(&world).all(|e| e.vel.magnitude() == 0.0));
+
+        // the floor holds up the whole pile, without the bottom of it sinking through
+        assert_eq!(world.entities[0].pos, (10.0, floor));
+    }
+
+    #[test]
+    fn test_islands_group_touching_entities() {
+        let mut world = make_piles(&[5.0, 20.0]);
+        let mut body = RigidBody::rectangle(EntityType::Npc, (30.0, 6.0), 2, 2).parts;
+        world.entities.append(&mut body);
+        for _ in 0..10 {
+            world.step();
+        }
+
+        // statics are left out of islands, so the piles aren't joined through the floor
+        let islands = sleep::islands(&world.entities, &world.joints);
+        assert_eq!(
+            islands,
+            vec![vec![39, 40, 41], vec![42, 43, 44], vec![45, 46, 47, 48]]
+        );
+    }
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod test_entity_vector {
-    use ascii_arcade::entity::{collision, vector::*};
+    use ascii_arcade::entity::{collision, vector::*, Entity, EntityType};
 
     /// Represents the initial and expected velocity values preceding and following a collision
     #[derive(Debug)]
@@ -91,6 +91,35 @@ mod test_entity_vector {
         }
     }
 
+    /// returns the velocities of two (elastic) entities at the passed points, moving at the passed
+    /// velocities, once the collision between them has been resolved.
+    fn resolve(
+        points: ((f32, f32), (f32, f32)),
+        vels: (EuclidianVector, EuclidianVector),
+    ) -> (EuclidianVector, EuclidianVector) {
+        // brought slightly closer together, so the entities are overlapping
+        let place = |point: (f32, f32), vel: EuclidianVector| {
+            let mut entity =
+                Entity::new(EntityType::Npc, (5.0 + point.0 * 0.9, 5.0 + point.1 * 0.9));
+            entity.vel = vel;
+            entity.restitution = 1.0;
+            entity
+        };
+        let mut entities = vec![place(points.0, vels.0), place(points.1, vels.1)];
+        let contacts = collision::detect(&entities);
+        collision::resolve(&mut entities, &contacts);
+        (entities[0].vel.clone(), entities[1].vel.clone())
+    }
+
+    #[test]
+    fn test_collision_resolution_matches_collision_calc() {
+        for data in make_test_data() {
+            let (new_a_vel, new_b_vel) = resolve(data.points, data.initial_vels.clone());
+            assert!((new_a_vel.x, new_a_vel.y) == (data.expect_vels.0.x, data.expect_vels.0.y));
+            assert!((new_b_vel.x, new_b_vel.y) == (data.expect_vels.1.x, data.expect_vels.1.y));
+        }
+    }
+
     #[test]
     fn test_mapping_motion_onto_direction_of_collision() {
         for data in make_test_data() {
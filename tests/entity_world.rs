@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests_world {
+    use ascii_arcade::entity::*;
+
+    #[test]
+    fn test_worlds_with_different_settings_run_side_by_side() {
+        let mut top_down = PhysicsWorld::default();
+        let mut side_on = PhysicsWorld::new(WorldConfig::side_view());
+        for world in [&mut top_down, &mut side_on] {
+            world
+                .entities
+                .push(Entity::new(EntityType::Npc, (5.0, 2.0)));
+            world
+                .entities
+                .push(Entity::new(EntityType::Static, (8.0, 2.0)));
+        }
+
+        for _ in 0..50 {
+            top_down.step();
+            side_on.step();
+        }
+
+        // only the npc in the side-view world falls, and statics never do
+        assert_eq!(top_down.entities[0].pos, (5.0, 2.0));
+        assert!(side_on.entities[0].pos.1 > 3.0);
+        assert!((side_on.entities[0].vel.y - 9.81 * 0.5).abs() < 1e-3);
+        assert_eq!(side_on.entities[1].pos, (8.0, 2.0));
+    }
+
+    #[test]
+    fn test_entities_are_kept_within_the_world_bounds() {
+        let mut world = PhysicsWorld::new(WorldConfig {
+            bounds: Bounds {
+                left: 0.0,
+                top: 0.0,
+                right: 100.0,
+                bottom: 30.0,
+            },
+            ..WorldConfig::side_view()
+        });
+        let mut entity = Entity::new(EntityType::Npc, (90.0, 5.0));
+        entity.vel.x = 20.0;
+        world.entities.push(entity);
+
+        for _ in 0..500 {
+            world.step();
+        }
+
+        // well beyond the default window, but stopped at the edges of the world
+        let entity = &world.entities[0];
+        assert_eq!(entity.pos, (100.0, 30.0));
+        assert!(entity.grounded);
+    }
+
+    #[test]
+    fn test_limits_and_time_step_are_configurable() {
+        let mut world = PhysicsWorld::new(WorldConfig {
+            time_step: 0.1,
+            max_vel: 2.0,
+            ..Default::default()
+        });
+        let mut entity = Entity::new(EntityType::Npc, (5.0, 5.0));
+        let force = entity.target_vel(10.0, 0.0, &world.config);
+        entity.apply_force(force);
+        world.entities.push(entity);
+
+        world.step();
+        assert_eq!(world.entities[0].vel.x, 2.0);
+
+        // moving at the limited speed for the (longer) time step
+        let x = world.entities[0].pos.0;
+        world.step();
+        assert!((world.entities[0].pos.0 - x - 0.2).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_default_bounds_match_the_default_window() {
+        let bounds = WorldConfig::default().bounds;
+        assert_eq!(bounds, Bounds::window(DEFAULT_WINDOW));
+        assert_eq!((bounds.right, bounds.bottom), (49.0, 9.0));
+    }
+}
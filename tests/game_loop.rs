@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod tests_clock {
+    use ascii_arcade::game_loop::Clock;
+    use std::time::Duration;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_ticks_are_fixed_regardless_of_frame_time() {
+        let mut clock = Clock::new(0.01, 60.0);
+
+        // leftover time is carried over, rather than rounded into a tick
+        assert_eq!(clock.advance(ms(25)), 2);
+        assert!((clock.alpha() - 0.5).abs() < 1e-3);
+        assert_eq!(clock.advance(ms(4)), 0);
+        assert_eq!(clock.advance(ms(1)), 1);
+        assert!(clock.alpha() < 1e-3);
+    }
+
+    #[test]
+    fn test_slow_frames_do_not_build_a_backlog_of_ticks() {
+        let mut clock = Clock::new(0.01, 60.0);
+        assert_eq!(clock.advance(ms(1_000)), 10);
+        assert_eq!(clock.advance(ms(10)), 1);
+    }
+
+    #[test]
+    fn test_clocks_without_a_tick_only_draw_frames() {
+        for tick in [0.0, -0.01] {
+            let mut clock = Clock::new(tick, 20.0);
+            assert_eq!(clock.advance(ms(10)), 0);
+            assert_eq!(clock.alpha(), 0.0);
+            assert!((clock.until_next().as_secs_f32() - 0.04).abs() < 1e-4);
+            assert_eq!(clock.advance(ms(40)), 0);
+            assert!(clock.frame_due());
+        }
+    }
+
+    #[test]
+    fn test_frames_are_drawn_at_their_own_rate() {
+        let mut clock = Clock::new(0.01, 20.0);
+        let mut ticks = 0;
+        let mut frames = 0;
+        for _ in 0..100 {
+            ticks += clock.advance(ms(10));
+            if clock.frame_due() {
+                frames += 1;
+            }
+        }
+        // a second of real time
+        assert_eq!(ticks, 100);
+        assert_eq!(frames, 20);
+    }
+
+    #[test]
+    fn test_waits_until_the_next_tick_or_frame() {
+        let mut clock = Clock::new(0.01, 20.0);
+        clock.advance(ms(4));
+        assert!((clock.until_next().as_secs_f32() - 0.006).abs() < 1e-4);
+
+        let mut clock = Clock::new(0.1, 100.0);
+        clock.advance(ms(4));
+        assert!((clock.until_next().as_secs_f32() - 0.006).abs() < 1e-4);
+    }
+}
+
+#[cfg(test)]
+mod tests_interpolation {
+    use ascii_arcade::entity::*;
+    use ascii_arcade::game_loop::interpolate;
+    use std::f32::consts::TAU;
+
+    #[test]
+    fn test_entities_are_drawn_between_ticks() {
+        let then = vec![Entity::new(EntityType::Npc, (2.0, 4.0))];
+        let mut now = then.clone();
+        now[0].pos = (3.0, 2.0);
+
+        assert_eq!(interpolate(&then, &now, 0.0)[0].pos, (2.0, 4.0));
+        assert_eq!(interpolate(&then, &now, 0.25)[0].pos, (2.25, 3.5));
+        assert_eq!(interpolate(&then, &now, 1.0)[0].pos, (3.0, 2.0));
+    }
+
+    #[test]
+    fn test_orientation_turns_the_shorter_way_round() {
+        let then = vec![Entity {
+            orientation: TAU - 0.2,
+            ..Entity::default()
+        }];
+        let mut now = then.clone();
+        now[0].orientation = 0.2;
+
+        let orientation = interpolate(&then, &now, 0.75)[0].orientation;
+        assert!((orientation - 0.1).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_new_entities_are_drawn_where_they_are() {
+        let then = vec![Entity::new(EntityType::Player, (2.0, 2.0))];
+        let mut now = vec![Entity::new(EntityType::Npc, (8.0, 8.0)), then[0].clone()];
+        now[1].pos = (4.0, 2.0);
+
+        // and those stored after them are still drawn between ticks
+        let frame = interpolate(&then, &now, 0.5);
+        assert_eq!(frame[0].pos, (8.0, 8.0));
+        assert_eq!(frame[1].pos, (3.0, 2.0));
+    }
+
+    #[test]
+    fn test_entities_after_despawned_ones_are_drawn_between_ticks() {
+        let then = vec![
+            Entity::new(EntityType::Npc, (8.0, 8.0)),
+            Entity::new(EntityType::Player, (2.0, 2.0)),
+        ];
+        let mut now = vec![then[1].clone()];
+        now[0].pos = (4.0, 2.0);
+
+        let frame = interpolate(&then, &now, 0.5);
+        assert_eq!(frame.len(), 1);
+        assert_eq!(frame[0].pos, (3.0, 2.0));
+    }
+}
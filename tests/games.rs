@@ -0,0 +1,53 @@
+#[cfg(test)]
+mod tests_sandbox {
+    use ascii_arcade::entity::*;
+    use ascii_arcade::games::SandboxGame;
+
+    /// returns how far (and for how many updates) a braking player let go of at 20 u/s slides
+    fn player_stopping_distance(mut world: PhysicsWorld, pos: (f32, f32)) -> (f32, usize) {
+        let mut player = Entity::new(EntityType::Player, pos);
+        player.vel.x = 20.0;
+        let uuid = world.spawn(player);
+        let player = world.entities.len() - 1;
+
+        for updates in 1..=1000 {
+            SandboxGame::brake(uuid, &mut world);
+            world.step();
+            if world.entities[player].vel.x.abs() < 1e-2 {
+                return (world.entities[player].pos.0 - pos.0, updates);
+            }
+        }
+        panic!("the player never stopped sliding");
+    }
+
+    #[test]
+    fn test_players_stop_within_a_few_cells_on_the_window_floor() {
+        let world = PhysicsWorld::new(WorldConfig::side_view());
+        let floor = world.config.bounds.bottom;
+        let (distance, updates) = player_stopping_distance(world, (5.0, floor));
+        assert!(distance < 3.0, "slid {distance} cells");
+        assert!(updates < 100, "slid for {updates} updates");
+    }
+
+    #[test]
+    fn test_players_stop_within_a_few_cells_on_statics() {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        world.entities = (1..40)
+            .map(|x| Entity::new(EntityType::Static, (x as f32, 6.0)))
+            .collect();
+        let (distance, updates) = player_stopping_distance(world, (5.0, 5.0));
+        assert!(distance < 3.0, "slid {distance} cells");
+        assert!(updates < 100, "slid for {updates} updates");
+    }
+
+    #[test]
+    fn test_players_are_not_braked_in_the_air() {
+        let mut world = PhysicsWorld::new(WorldConfig::side_view());
+        let mut player = Entity::new(EntityType::Player, (5.0, 1.0));
+        player.vel.x = 20.0;
+        let uuid = world.spawn(player);
+        SandboxGame::brake(uuid, &mut world);
+        world.step();
+        assert_eq!(world.entities[0].vel.x, 20.0);
+    }
+}
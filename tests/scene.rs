@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod tests_scene {
+    use ascii_arcade::entity::{Entity, EntityType, Hitbox};
+    use ascii_arcade::scene::*;
+
+    #[test]
+    fn test_cells_left_behind_are_cleared() {
+        let mut platform = Entity::new(EntityType::Static, (20.0, 8.0));
+        platform.hitbox = Hitbox::Aabb {
+            half_width: 1.5,
+            half_height: 0.5,
+        };
+        let then = vec![
+            Entity::new(EntityType::Npc, (5.0, 5.0)),
+            Entity::new(EntityType::Npc, (10.0, 5.0)),
+            platform,
+            Entity::new(EntityType::Npc, (15.0, 5.0)),
+        ];
+
+        // the first entity and the platform removed, shifting the others down the list,
+        // and the last entity moved one cell along
+        let mut now = vec![then[1].clone(), then[3].clone()];
+        now[1].pos = (16.0, 5.0);
+
+        assert_eq!(
+            stale_cells(&then, &now),
+            vec![(5, 5), (19, 8), (20, 8), (21, 8), (15, 5)]
+        );
+
+        // nothing is cleared where an entity still is, even if a different one
+        now.push(Entity::new(EntityType::Npc, (5.0, 5.0)));
+        assert!(!stale_cells(&then, &now).contains(&(5, 5)));
+        assert!(stale_cells(&now, &now).is_empty());
+    }
+
+    #[test]
+    fn test_overlapping_cells_are_cleared_once() {
+        // a platform overlapping entities on either side of another, elsewhere
+        let mut platform = Entity::new(EntityType::Static, (6.0, 5.0));
+        platform.hitbox = Hitbox::Aabb {
+            half_width: 1.5,
+            half_height: 0.5,
+        };
+        let then = vec![
+            Entity::new(EntityType::Npc, (5.0, 5.0)),
+            Entity::new(EntityType::Npc, (20.0, 8.0)),
+            platform,
+            Entity::new(EntityType::Npc, (7.0, 5.0)),
+        ];
+        assert_eq!(
+            stale_cells(&then, &[]),
+            vec![(5, 5), (20, 8), (6, 5), (7, 5)]
+        );
+    }
+}